@@ -0,0 +1,32 @@
+use std::cmp::Reverse;
+
+use crate::{
+    game::{Game, Status},
+    players::MinMaxPlayer,
+};
+
+/// Prints the state, its status and the min-max value of every available move. The moves are
+/// written with their labels (see `Game::label`), as the human player types them.
+///
+/// The state is solved from scratch, so it's only feasible for small games or for states close to
+/// the end of the game.
+pub(crate) fn analyze<G: Game>(game: &G, state: &G::State) {
+    println!("{}", game.labelled_board(state));
+
+    let player = game.current_player(state);
+
+    match game.status(state) {
+        Status::Finished(Some(winner)) => println!("Status: {winner} won"),
+        Status::Finished(None) => println!("Status: Draw"),
+        Status::OnGoing => {
+            println!("Status: On going, {player} to move");
+
+            let mut action_values = MinMaxPlayer::new(true).action_values(game, state, player);
+            action_values.sort_by_key(|(_, value)| Reverse(value.score()));
+
+            for (action, value) in action_values {
+                println!("{}: {value}", game.label(action));
+            }
+        }
+    }
+}
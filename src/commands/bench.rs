@@ -0,0 +1,109 @@
+use std::time::{Duration, Instant};
+
+use crate::game::{Game, Player, Status, TicTacToe};
+
+/// How long each search is repeated for.
+const DURATION: Duration = Duration::from_secs(1);
+
+/// Measures how many nodes per second a full Tic-Tac-Toe search visits, first cloning the state
+/// for every move and then making and unmaking the moves in place (see `Game::undo`). The min-max
+/// and alpha-beta agents search in place the games that can undo their moves, like Tic-Tac-Toe,
+/// and clone the state in the others.
+pub(crate) fn bench() {
+    measure("Clone", |nodes| {
+        clone_search(
+            &TicTacToe::default(),
+            &TicTacToe::default().initial_state(),
+            Player::X,
+            nodes,
+        );
+    });
+    measure("Make/unmake", |nodes| {
+        make_unmake_search(
+            &TicTacToe::default(),
+            &mut TicTacToe::default().initial_state(),
+            Player::X,
+            nodes,
+        );
+    });
+}
+
+/// Repeats the `search` for `DURATION` and prints how many nodes it visited per second.
+fn measure(name: &str, search: impl Fn(&mut u64)) {
+    let mut nodes = 0;
+    let mut searches = 0;
+    let start = Instant::now();
+
+    while start.elapsed() < DURATION {
+        search(&mut nodes);
+        searches += 1;
+    }
+
+    let elapsed = start.elapsed().as_secs_f64();
+    println!(
+        "{name}: {nodes} nodes in {searches} searches, {elapsed:.2}s ({:.2}M nodes/s)",
+        nodes as f64 / elapsed / 1e6
+    );
+}
+
+/// The negamax value of the state for the `player` to move, without any pruning so every node is
+/// visited.
+fn clone_search<G: Game>(game: &G, state: &G::State, player: Player, nodes: &mut u64) -> i8 {
+    *nodes += 1;
+
+    if let Status::Finished(winner) = game.status(state) {
+        return terminal_value(winner, player);
+    }
+
+    game.available_moves(state)
+        .into_iter()
+        .map(|action| {
+            let mut next_state = state.clone();
+
+            // SAFETY: we draw the actions from the `available_moves` method
+            unsafe { game.act(player, action, &mut next_state).unwrap_unchecked() };
+
+            -clone_search(game, &next_state, player.next_player(), nodes)
+        })
+        .max()
+        .unwrap_or_default()
+}
+
+/// The same search as `clone_search`, but reusing a single state. The game must be able to undo
+/// its moves, see `Game::can_undo`.
+fn make_unmake_search<G: Game>(
+    game: &G,
+    state: &mut G::State,
+    player: Player,
+    nodes: &mut u64,
+) -> i8 {
+    *nodes += 1;
+
+    if let Status::Finished(winner) = game.status(state) {
+        return terminal_value(winner, player);
+    }
+
+    let mut best_value = i8::MIN;
+
+    for action in game.available_moves(state) {
+        // SAFETY: we draw the actions from the `available_moves` method
+        unsafe { game.act(player, action, state).unwrap_unchecked() };
+        best_value = best_value.max(-make_unmake_search(
+            game,
+            state,
+            player.next_player(),
+            nodes,
+        ));
+        game.undo(player, action, state);
+    }
+
+    best_value
+}
+
+fn terminal_value(winner: Option<Player>, player: Player) -> i8 {
+    match winner {
+        Some(winner) if winner == player => 1,
+        Some(_) => -1,
+        None => 0,
+    }
+}
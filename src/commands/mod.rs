@@ -1,3 +1,18 @@
+mod analyze;
+mod bench;
 mod play;
+mod record;
+mod replay;
+mod report;
+mod session;
+mod statistics;
+mod tournament;
 
-pub(crate) use play::play;
+pub(crate) use analyze::analyze;
+pub(crate) use bench::bench;
+pub(crate) use play::{play, MatchOptions};
+pub(crate) use replay::replay;
+pub(crate) use report::{Format, PlayerDescription};
+pub(crate) use session::play_human;
+pub(crate) use statistics::Sprt;
+pub(crate) use tournament::tournament;
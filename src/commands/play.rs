@@ -1,86 +1,383 @@
-use std::{fmt::Display, ops::AddAssign, thread};
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    fs,
+    ops::AddAssign,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Mutex,
+    },
+    thread,
+    time::Instant,
+};
+
+use fastrand::Rng;
+use serde::Serialize;
 
 use crate::{
-    game::{self, Game},
+    game::{self, splitmix64, Game, IllegalMovePolicy},
     players::Player,
+    ReLearnError,
+};
+
+use super::{
+    record::Recorder,
+    report::{Format, PlayerDescription, Report},
+    statistics::{elo_difference, score_interval, wilson_interval, Hypothesis, Sprt},
 };
 
-struct GamesResult {
-    victories: u32,
-    draws: u32,
-    losses: u32,
+/// The results from the point of view of the first player.
+#[derive(Default)]
+pub(super) struct GamesResult {
+    /// The games where the first player plays as X, and so makes the first move.
+    pub(super) as_x: SeatResult,
+    /// The games where the first player plays as O.
+    pub(super) as_o: SeatResult,
+    /// How many games ended after each number of moves, indexed by the number of moves. Only the
+    /// moves of the players count, not the chance events.
+    pub(super) lengths: Vec<u32>,
+    /// How many illegal moves the first and the second player tried.
+    pub(super) illegal_moves: [u32; 2],
+    /// How many games the first and the second player lost for trying illegal moves.
+    pub(super) forfeits: [u32; 2],
+    /// The illegal move of the first and the second player in the earliest game where they tried
+    /// one.
+    pub(super) first_illegal_moves: [Option<IllegalMove>; 2],
 }
 
-pub(crate) fn play(player_1: &dyn Player, player_2: &dyn Player, game_count: u32) {
-    let mut games_results = GamesResult {
-        victories: 0,
-        draws: 0,
-        losses: 0,
-    };
+/// An illegal move, with the state where it was tried, to find out what went wrong.
+#[derive(Clone, Serialize)]
+pub(super) struct IllegalMove {
+    /// The index of the game in the match.
+    pub(super) game_index: u32,
+    /// See `MoveError`.
+    pub(super) description: String,
+}
+
+#[derive(Default, Clone, Copy, Serialize)]
+pub(super) struct SeatResult {
+    pub(super) victories: u32,
+    pub(super) draws: u32,
+    pub(super) losses: u32,
+}
 
-    let available_parallelism = usize::min(
-        std::thread::available_parallelism().unwrap().get(),
-        game_count as usize,
+/// How the match is played and where its results go.
+pub(crate) struct MatchOptions {
+    /// The maximum number of games when playing with a `sprt`.
+    pub(crate) game_count: u32,
+    pub(crate) sprt: Option<Sprt>,
+    pub(crate) format: Format,
+    /// Where the results are written, the standard output when `None`.
+    pub(crate) output: Option<PathBuf>,
+    /// Where the records of the games are written, see `Record`.
+    pub(crate) record: Option<PathBuf>,
+    /// The seed of the random number generators, see `play_match`.
+    pub(crate) seed: u64,
+    pub(crate) illegal_moves: IllegalMovePolicy,
+}
+
+pub(crate) fn play<G: Game>(
+    game: &G,
+    player_1: &dyn Player<G>,
+    player_2: &dyn Player<G>,
+    descriptions: &[PlayerDescription; 2],
+    options: &MatchOptions,
+) -> Result<(), ReLearnError> {
+    let recorder = options
+        .record
+        .as_ref()
+        .map(|_| Recorder::new([descriptions[0].name.clone(), descriptions[1].name.clone()]));
+
+    let start = Instant::now();
+    let games_results = play_match(
+        game,
+        [player_1, player_2],
+        options.game_count,
+        options.sprt.as_ref(),
+        recorder.as_ref(),
+        options.seed,
+        options.illegal_moves,
     );
+    let elapsed = start.elapsed();
 
-    thread::scope(|s| {
-        let mut handlers = Vec::with_capacity(available_parallelism);
-
-        for _ in 0..available_parallelism {
-            handlers.push(s.spawn(|| {
-                play_games(
-                    player_1,
-                    player_2,
-                    // NOTE: This code is not correct because it just truncates the division result,
-                    // but it's fine for this application.
-                    game_count as usize / available_parallelism,
-                )
-            }));
+    if let (Some(recorder), Some(path)) = (recorder, &options.record) {
+        recorder.write(path)?;
+    }
+
+    let report = || {
+        Report::new(
+            game,
+            descriptions,
+            &games_results,
+            options.sprt.as_ref(),
+            elapsed,
+            options.seed,
+        )
+    };
+
+    let output = match options.format {
+        Format::Text => {
+            let mut output = format!(
+                "{games_results}Time: {:.2}s ({:.2} games/s)\nSeed: {}\n",
+                elapsed.as_secs_f64(),
+                games_results.total().game_count() as f64 / elapsed.as_secs_f64(),
+                options.seed
+            );
+
+            if let Some(sprt) = options.sprt {
+                let total = games_results.total();
+                let (lower, upper) = sprt.bounds();
+                let decision = match sprt.decision(&total) {
+                    Some(Hypothesis::Null) => "H0 accepted",
+                    Some(Hypothesis::Alternative) => "H1 accepted",
+                    None => "inconclusive",
+                };
+
+                output += &format!(
+                    "SPRT ({sprt}): {decision}, LLR: {:.2} [{lower:.2}, {upper:.2}]\n",
+                    sprt.log_likelihood_ratio(&total)
+                );
+            }
+
+            output
         }
+        Format::Json => report().to_json()?,
+        Format::Csv => report().to_csv()?,
+    };
 
-        for handler in handlers {
-            games_results += handler.join().unwrap();
+    match &options.output {
+        Some(path) => {
+            fs::write(path, output).map_err(|err| ReLearnError::OutputError(err.to_string()))
         }
+        None => {
+            print!("{output}");
+            Ok(())
+        }
+    }
+}
+
+/// Plays exactly `game_count` games in parallel, the players alternate who starts.
+///
+/// With a `sprt` the match stops as soon as one of its hypotheses is accepted, so `game_count` is
+/// just an upper bound.
+///
+/// Every game has its own random number generator, seeded from `seed` and the index of the game
+/// (see `derive_seed`), so the results only depend on the seed and not on the threads that played the
+/// games, see `play_games`.
+pub(super) fn play_match<G: Game>(
+    game: &G,
+    [player_1, player_2]: [&dyn Player<G>; 2],
+    game_count: u32,
+    sprt: Option<&Sprt>,
+    recorder: Option<&Recorder>,
+    seed: u64,
+    policy: IllegalMovePolicy,
+) -> GamesResult {
+    let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+
+    let games_results = play_games(game_count, sprt, threads, |index| {
+        play_game(
+            game,
+            player_1,
+            player_2,
+            index,
+            derive_seed(seed, index),
+            recorder,
+            policy,
+        )
     });
 
-    print!("{games_results}");
+    // The games past the one that stopped the match are not part of it
+    if let Some(recorder) = recorder {
+        recorder.truncate(games_results.total().game_count());
+    }
+
+    games_results
 }
 
-fn play_games(player_1: &dyn Player, player_2: &dyn Player, n: usize) -> GamesResult {
-    let mut victories = 0;
-    let mut draws = 0;
-    let mut losses = 0;
+/// The seed of the game (or the match of a tournament) with the given index. The seed is mixed
+/// before adding the index, so the runs with close seeds (e.g., `42` and `43`) don't play the same
+/// games shifted by a few indices.
+pub(super) fn derive_seed(seed: u64, index: u32) -> u64 {
+    splitmix64(splitmix64(seed) ^ index as u64)
+}
 
-    let mut first_player = false;
+/// Plays the games in the given number of threads, which take them in order.
+///
+/// The results are added in the order of the games, the ones that finish before an earlier game
+/// wait for it. So a `sprt` decides after the same games whatever thread finished them first, and
+/// the games that were still being played when it stopped the match are dropped.
+fn play_games(
+    game_count: u32,
+    sprt: Option<&Sprt>,
+    threads: usize,
+    play_game: impl Fn(u32) -> GamesResult + Sync,
+) -> GamesResult {
+    let games_results = Mutex::new(GamesResult::default());
+    // The results of the games that finished before an earlier game, by their index
+    let pending_results = Mutex::new(BTreeMap::new());
+    let next_game = AtomicU32::new(0);
+    let finished = AtomicBool::new(false);
 
-    for _ in 0..n {
-        // We alternate the players
-        first_player = !first_player;
+    thread::scope(|s| {
+        for _ in 0..threads.min(game_count as usize) {
+            s.spawn(|| loop {
+                let index = next_game.fetch_add(1, Ordering::Relaxed);
 
-        let result = if first_player {
-            Game::play(player_1, player_2)
-        } else {
-            Game::play(player_2, player_1)
-        };
+                if index >= game_count || finished.load(Ordering::Relaxed) {
+                    break;
+                }
 
-        match result {
-            Some(player) => match player {
-                game::Player::X if first_player => victories += 1,
-                game::Player::O if !first_player => victories += 1,
-                game::Player::X | game::Player::O => losses += 1,
-            },
-            None => draws += 1,
-        };
+                let game_result = play_game(index);
+
+                let mut games_results = games_results.lock().unwrap();
+                let mut pending_results = pending_results.lock().unwrap();
+                pending_results.insert(index, game_result);
+
+                // Adds the results that follow the ones already added, until the match stops
+                while !finished.load(Ordering::Relaxed) {
+                    let next_index = games_results.total().game_count();
+                    let Some(game_result) = pending_results.remove(&next_index) else {
+                        break;
+                    };
+
+                    *games_results += game_result;
+
+                    if sprt.is_some_and(|sprt| sprt.decision(&games_results.total()).is_some()) {
+                        finished.store(true, Ordering::Relaxed);
+                    }
+                }
+            });
+        }
+    });
+
+    games_results.into_inner().unwrap()
+}
+
+/// Plays the game with the given index, the first player starts the even ones.
+fn play_game<G: Game>(
+    game: &G,
+    player_1: &dyn Player<G>,
+    player_2: &dyn Player<G>,
+    index: u32,
+    seed: u64,
+    recorder: Option<&Recorder>,
+    policy: IllegalMovePolicy,
+) -> GamesResult {
+    let mut games_result = GamesResult::default();
+    let mut rng = Rng::with_seed(seed);
+
+    // We alternate the players
+    let first_player = index.is_multiple_of(2);
+
+    let (record, seat, first_player_seat) = if first_player {
+        (
+            game.play(player_1, player_2, policy, &mut rng),
+            &mut games_result.as_x,
+            game::Player::X,
+        )
+    } else {
+        (
+            game.play(player_2, player_1, policy, &mut rng),
+            &mut games_result.as_o,
+            game::Player::O,
+        )
+    };
+
+    match record.winner {
+        Some(winner) if winner == first_player_seat => seat.victories += 1,
+        Some(_) => seat.losses += 1,
+        None => seat.draws += 1,
+    };
+
+    if let Some(recorder) = recorder {
+        recorder.add(game, index, seed, first_player_seat, &record);
     }
 
-    GamesResult {
-        victories,
-        draws,
-        losses,
+    games_result.lengths = vec![0; record.decisions.len() + 1];
+    games_result.lengths[record.decisions.len()] = 1;
+    for (seat, player) in [first_player_seat, first_player_seat.next_player()]
+        .into_iter()
+        .enumerate()
+    {
+        let errors: Vec<_> = record
+            .illegal_moves
+            .iter()
+            .filter(|(illegal_move_player, _)| *illegal_move_player == player)
+            .map(|(_, error)| error)
+            .collect();
+
+        games_result.illegal_moves[seat] = errors.len() as u32;
+        games_result.first_illegal_moves[seat] = errors.first().map(|error| IllegalMove {
+            game_index: index,
+            description: error.to_string(),
+        });
+        games_result.forfeits[seat] = (record.forfeit == Some(player)) as u32;
+    }
+
+    games_result
+}
+
+impl GamesResult {
+    /// The results of both seats together.
+    pub(super) fn total(&self) -> SeatResult {
+        let mut total = self.as_x;
+        total += self.as_o;
+        total
+    }
+
+    pub(super) fn average_length(&self) -> f64 {
+        let (games, moves) =
+            self.lengths
+                .iter()
+                .enumerate()
+                .fold((0, 0), |(games, moves), (length, &count)| {
+                    (games + count, moves + length as u64 * count as u64)
+                });
+
+        moves as f64 / games.max(1) as f64
+    }
+}
+
+impl SeatResult {
+    pub(super) fn game_count(&self) -> u32 {
+        self.victories + self.draws + self.losses
     }
 }
 
 impl AddAssign for GamesResult {
+    fn add_assign(&mut self, rhs: Self) {
+        self.as_x += rhs.as_x;
+        self.as_o += rhs.as_o;
+        if self.lengths.len() < rhs.lengths.len() {
+            self.lengths.resize(rhs.lengths.len(), 0);
+        }
+        for (count, rhs_count) in self.lengths.iter_mut().zip(rhs.lengths) {
+            *count += rhs_count;
+        }
+        for seat in 0..2 {
+            self.illegal_moves[seat] += rhs.illegal_moves[seat];
+            self.forfeits[seat] += rhs.forfeits[seat];
+        }
+        for (illegal_move, rhs_illegal_move) in self
+            .first_illegal_moves
+            .iter_mut()
+            .zip(rhs.first_illegal_moves)
+        {
+            // The games finish in any order, so we keep the earliest one to be reproducible
+            if let Some(rhs_illegal_move) = rhs_illegal_move {
+                if illegal_move.as_ref().is_none_or(|illegal_move| {
+                    rhs_illegal_move.game_index < illegal_move.game_index
+                }) {
+                    *illegal_move = Some(rhs_illegal_move);
+                }
+            }
+        }
+    }
+}
+
+impl AddAssign for SeatResult {
     fn add_assign(&mut self, rhs: Self) {
         self.victories += rhs.victories;
         self.draws += rhs.draws;
@@ -90,15 +387,228 @@ impl AddAssign for GamesResult {
 
 impl Display for GamesResult {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let game_count = self.victories + self.draws + self.losses;
+        let total = self.total();
+
+        writeln!(f, "Player 1 as X: {}", self.as_x)?;
+        writeln!(f, "Player 1 as O: {}", self.as_o)?;
+        writeln!(f, "Total:         {total}")?;
+
+        let (lower, upper) = wilson_interval(total.victories, total.game_count());
+        writeln!(
+            f,
+            "Win rate (95% Wilson interval): [{:.2}%, {:.2}%]",
+            lower * 100.0,
+            upper * 100.0
+        )?;
+
+        let (score, margin) = score_interval(&total);
+        writeln!(
+            f,
+            "Score (95% normal interval): {:.2}% ± {:.2}%",
+            score * 100.0,
+            margin * 100.0
+        )?;
+
+        // A perfect score has an infinite Elo difference, so the interval is clamped to [0, 1]
+        writeln!(
+            f,
+            "Elo difference: {:+.1} [{:+.1}, {:+.1}]",
+            elo_difference(score),
+            elo_difference((score - margin).max(0.0)),
+            elo_difference((score + margin).min(1.0))
+        )?;
+
+        writeln!(f, "Average game length: {:.2} moves", self.average_length())?;
+        writeln!(
+            f,
+            "Illegal moves: player 1: {}, player 2: {}",
+            self.illegal_moves[0], self.illegal_moves[1]
+        )?;
+        writeln!(
+            f,
+            "Forfeits: player 1: {}, player 2: {}",
+            self.forfeits[0], self.forfeits[1]
+        )?;
+
+        for (seat, illegal_move) in self.first_illegal_moves.iter().enumerate() {
+            if let Some(illegal_move) = illegal_move {
+                write!(
+                    f,
+                    "First illegal move of player {} (game {}): {}",
+                    seat + 1,
+                    illegal_move.game_index,
+                    illegal_move.description
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for SeatResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let game_count = self.game_count();
+        // Avoids dividing by zero when no game was played in this seat
+        let percentage = |count: u32| count as f64 / game_count.max(1) as f64 * 100.0;
 
         write!(
             f,
             "Win: {:.2}%, Draw: {:.2}%, Loss: {:.2}%, Game Count: {}",
-            (self.victories as f64 / game_count as f64) * 100.0,
-            (self.draws as f64 / game_count as f64) * 100.0,
-            ((game_count - self.victories - self.draws) as f64 / game_count as f64) * 100.0,
+            percentage(self.victories),
+            percentage(self.draws),
+            percentage(self.losses),
             game_count
         )
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::{
+        game::{KuhnPoker, TicTacToe},
+        players::RandomPlayer,
+        ReLearnError,
+    };
+
+    type TicTacToeState = <TicTacToe as Game>::State;
+
+    /// Always plays out of the board.
+    struct OutOfBoundPlayer;
+
+    impl Player<TicTacToe> for OutOfBoundPlayer {
+        fn play(&self, _: &TicTacToe, _: &TicTacToeState, _: game::Player, _: &mut Rng) -> u8 {
+            9
+        }
+
+        fn learn(&mut self, _: &TicTacToe, _: &mut Rng) {}
+
+        fn save(&self, _: &TicTacToe) -> Result<(), ReLearnError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_play_match() {
+        let play = |game_count, seed| {
+            play_match(
+                &TicTacToe::default(),
+                [&RandomPlayer, &RandomPlayer],
+                game_count,
+                None,
+                None,
+                seed,
+                IllegalMovePolicy::Forfeit,
+            )
+        };
+
+        for game_count in [0, 1, 7, 100] {
+            let result = play(game_count, 42);
+
+            assert_eq!(result.total().game_count(), game_count);
+            assert_eq!(result.as_x.game_count(), game_count.div_ceil(2));
+            assert_eq!(result.as_o.game_count(), game_count / 2);
+            assert_eq!(result.illegal_moves, [0, 0]);
+            assert_eq!(result.lengths.iter().sum::<u32>(), game_count);
+            assert!(result.lengths.iter().take(5).all(|&count| count == 0));
+        }
+
+        // The deal of the cards is not a move, so every game of Kuhn poker lasts 2 or 3 moves
+        let result = play_match(
+            &KuhnPoker,
+            [&RandomPlayer, &RandomPlayer],
+            100,
+            None,
+            None,
+            42,
+            IllegalMovePolicy::Forfeit,
+        );
+        assert_eq!(result.lengths[2] + result.lengths[3], 100);
+
+        // The same seed always gives the same results
+        let result = play(100, 7);
+        let other_result = play(100, 7);
+        assert_eq!(result.as_x.victories, other_result.as_x.victories);
+        assert_eq!(result.as_o.losses, other_result.as_o.losses);
+        assert_eq!(result.lengths, other_result.lengths);
+
+        // The matches with close seeds don't share the seeds of their games
+        let seeds: HashSet<_> = (0..100)
+            .flat_map(|index| [derive_seed(7, index), derive_seed(8, index)])
+            .collect();
+        assert_eq!(seeds.len(), 200);
+
+        // Even when the SPRT stops the match, whatever the number of threads
+        let sprt: Sprt = "0,200,0.05,0.05".parse().unwrap();
+        let play_with_sprt = |threads| {
+            play_games(1000, Some(&sprt), threads, |index| {
+                play_game(
+                    &TicTacToe::default(),
+                    &RandomPlayer,
+                    &RandomPlayer,
+                    index,
+                    derive_seed(7, index),
+                    None,
+                    IllegalMovePolicy::Forfeit,
+                )
+            })
+        };
+
+        let result = play_with_sprt(1);
+        assert!(result.total().game_count() < 1000);
+        assert!(sprt.decision(&result.total()).is_some());
+
+        for threads in [2, 4, 8] {
+            for _ in 0..5 {
+                let other_result = play_with_sprt(threads);
+                assert_eq!(result.as_x.game_count(), other_result.as_x.game_count());
+                assert_eq!(result.as_x.victories, other_result.as_x.victories);
+                assert_eq!(result.as_o.losses, other_result.as_o.losses);
+                assert_eq!(result.lengths, other_result.lengths);
+            }
+        }
+    }
+
+    #[test]
+    fn test_illegal_moves() {
+        let play = |policy| {
+            play_match(
+                &TicTacToe::default(),
+                [&RandomPlayer, &OutOfBoundPlayer],
+                10,
+                None,
+                None,
+                42,
+                policy,
+            )
+        };
+
+        let result = play(IllegalMovePolicy::Forfeit);
+        assert_eq!(result.total().victories, 10);
+        assert_eq!(result.illegal_moves, [0, 10]);
+        assert_eq!(result.forfeits, [0, 10]);
+        assert!(result.first_illegal_moves[0].is_none());
+
+        // The earliest game is kept, whatever thread finished it first
+        let illegal_move = result.first_illegal_moves[1].as_ref().unwrap();
+        assert_eq!(illegal_move.game_index, 0);
+        assert!(illegal_move
+            .description
+            .starts_with("Move 9: the move is out of the board"));
+
+        let result = play(IllegalMovePolicy::Retry(2));
+        assert_eq!(result.total().victories, 10);
+        assert_eq!(result.illegal_moves, [0, 30]);
+        assert_eq!(result.forfeits, [0, 10]);
+
+        // Every move of the second player is replaced by a random one, it plays at least 2 of the
+        // 5 or more moves of each game
+        let result = play(IllegalMovePolicy::Substitute);
+        assert_eq!(result.total().game_count(), 10);
+        assert_eq!(result.forfeits, [0, 0]);
+        assert!(result.illegal_moves[1] >= 20);
+    }
+}
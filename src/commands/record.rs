@@ -0,0 +1,288 @@
+use std::{fmt::Display, fs, path::Path, str::FromStr, sync::Mutex};
+
+use crate::{
+    game::{self, Game, GameRecord},
+    ReLearnError,
+};
+
+/// A recorded game. In the record files each field is written in a `Key: value` line and the
+/// games are separated by blank lines, e.g.:
+///
+/// ```text
+/// Game: tic-tac-toe
+/// Index: 0
+/// Seed: 11562961094431519461
+/// X: min-max
+/// O: random
+/// Result: X
+/// Forfeit: O
+/// Illegal moves: O 9, O 4
+/// Moves: 4 0 8
+/// ```
+///
+/// The forfeit is `-` when nobody gave the game up, and the illegal moves are empty when there were
+/// none.
+#[derive(Debug, PartialEq)]
+pub(super) struct Record {
+    /// The name of the game, see `Game::name`.
+    pub(super) game: String,
+    /// The index of the game in the match.
+    pub(super) index: u32,
+    /// The seed of the random number generator used in the game.
+    pub(super) seed: u64,
+    /// The names of the X and O players.
+    pub(super) players: [String; 2],
+    /// The winner, `None` for draws.
+    pub(super) winner: Option<game::Player>,
+    /// The player that gave the game up, by resigning or by trying too many illegal moves.
+    pub(super) forfeit: Option<game::Player>,
+    /// The illegal moves that each player tried, as displayed by the game, in the order they were
+    /// tried.
+    pub(super) illegal_moves: Vec<(game::Player, String)>,
+    /// The moves as displayed by the game, for Tic-Tac-Toe it's the index of the cell. It includes
+    /// the chance events and the moves played instead of the illegal ones, see `GameRecord::moves`.
+    pub(super) moves: Vec<String>,
+}
+
+/// Keeps the records of the games of a match, which may finish in any order.
+pub(super) struct Recorder {
+    /// The names of the first and the second player.
+    names: [String; 2],
+    records: Mutex<Vec<Record>>,
+}
+
+impl Recorder {
+    pub(super) fn new(names: [String; 2]) -> Self {
+        Recorder {
+            names,
+            records: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub(super) fn add<G: Game>(
+        &self,
+        game: &G,
+        index: u32,
+        seed: u64,
+        first_player: game::Player,
+        game_record: &GameRecord<G>,
+    ) {
+        let [first, second] = self.names.clone();
+        let players = match first_player {
+            game::Player::X => [first, second],
+            game::Player::O => [second, first],
+        };
+
+        self.records.lock().unwrap().push(Record {
+            game: game.name(),
+            index,
+            seed,
+            players,
+            winner: game_record.winner,
+            forfeit: game_record.forfeit,
+            illegal_moves: game_record
+                .illegal_moves
+                .iter()
+                .map(|(player, error)| (*player, error.action.to_string()))
+                .collect(),
+            moves: game_record
+                .moves
+                .iter()
+                .map(|action| action.to_string())
+                .collect(),
+        });
+    }
+
+    /// Drops the records of the games from the `game_count` index onwards.
+    pub(super) fn truncate(&self, game_count: u32) {
+        self.records
+            .lock()
+            .unwrap()
+            .retain(|record| record.index < game_count);
+    }
+
+    /// Writes the records to the file, ordered by their index.
+    pub(super) fn write(self, path: &Path) -> Result<(), ReLearnError> {
+        let mut records = self.records.into_inner().unwrap();
+        records.sort_by_key(|record| record.index);
+
+        let text = records
+            .iter()
+            .map(Record::to_string)
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        fs::write(path, text).map_err(|err| ReLearnError::OutputError(err.to_string()))
+    }
+}
+
+/// Parses all the records of a file.
+pub(super) fn parse_records(text: &str) -> Result<Vec<Record>, String> {
+    text.split("\n\n")
+        .filter(|record| !record.trim().is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+impl Display for Record {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let result = match self.winner {
+            Some(winner) => winner.to_string(),
+            None => "Draw".to_string(),
+        };
+        let forfeit = match self.forfeit {
+            Some(player) => player.to_string(),
+            None => "-".to_string(),
+        };
+        let illegal_moves: Vec<_> = self
+            .illegal_moves
+            .iter()
+            .map(|(player, action)| format!("{player} {action}"))
+            .collect();
+
+        writeln!(f, "Game: {}", self.game)?;
+        writeln!(f, "Index: {}", self.index)?;
+        writeln!(f, "Seed: {}", self.seed)?;
+        writeln!(f, "X: {}", self.players[0])?;
+        writeln!(f, "O: {}", self.players[1])?;
+        writeln!(f, "Result: {result}")?;
+        writeln!(f, "Forfeit: {forfeit}")?;
+        writeln!(f, "Illegal moves: {}", illegal_moves.join(", "))?;
+        writeln!(f, "Moves: {}", self.moves.join(" "))
+    }
+}
+
+impl FromStr for Record {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.trim().lines().map(|line| {
+            line.split_once(':')
+                .map(|(key, value)| (key.trim(), value.trim()))
+                .ok_or_else(|| format!("Invalid record line '{line}', expected 'Key: value'"))
+        });
+
+        let mut field = |expected_key: &str| {
+            let (key, value) = fields
+                .next()
+                .ok_or_else(|| format!("The record has no '{expected_key}' field"))??;
+
+            if key == expected_key {
+                Ok(value.to_string())
+            } else {
+                Err(format!(
+                    "Expected the '{expected_key}' field, found '{key}'"
+                ))
+            }
+        };
+
+        let game = field("Game")?;
+        let index = field("Index")?;
+        let seed = field("Seed")?;
+        let players = [field("X")?, field("O")?];
+        let winner = match field("Result")?.as_str() {
+            "X" => Some(game::Player::X),
+            "O" => Some(game::Player::O),
+            "Draw" => None,
+            result => return Err(format!("Invalid result '{result}'")),
+        };
+        let forfeit = match field("Forfeit")?.as_str() {
+            "-" => None,
+            player => Some(parse_player(player)?),
+        };
+        let illegal_moves = field("Illegal moves")?
+            .split(',')
+            .filter(|illegal_move| !illegal_move.trim().is_empty())
+            .map(|illegal_move| {
+                let (player, action) = illegal_move
+                    .trim()
+                    .split_once(' ')
+                    .ok_or_else(|| format!("Invalid illegal move '{illegal_move}'"))?;
+
+                Ok((parse_player(player)?, action.to_string()))
+            })
+            .collect::<Result<_, String>>()?;
+        let moves = field("Moves")?
+            .split_whitespace()
+            .map(str::to_string)
+            .collect();
+
+        Ok(Record {
+            game,
+            index: index
+                .parse()
+                .map_err(|_| format!("Invalid index '{index}'"))?,
+            seed: seed.parse().map_err(|_| format!("Invalid seed '{seed}'"))?,
+            players,
+            winner,
+            forfeit,
+            illegal_moves,
+            moves,
+        })
+    }
+}
+
+fn parse_player(player: &str) -> Result<game::Player, String> {
+    match player {
+        "X" => Ok(game::Player::X),
+        "O" => Ok(game::Player::O),
+        _ => Err(format!("Invalid player '{player}', expected 'X' or 'O'")),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_records() {
+        let record = Record {
+            game: "tic-tac-toe".to_string(),
+            index: 3,
+            seed: 42,
+            players: ["random".to_string(), "min-max".to_string()],
+            winner: Some(game::Player::O),
+            forfeit: None,
+            illegal_moves: Vec::new(),
+            moves: vec!["0", "4", "1", "2", "8", "6"]
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+        };
+        let draw = Record {
+            game: record.game.clone(),
+            index: 4,
+            seed: 7,
+            players: record.players.clone(),
+            winner: None,
+            forfeit: None,
+            illegal_moves: Vec::new(),
+            moves: Vec::new(),
+        };
+        let forfeit = Record {
+            game: record.game.clone(),
+            index: 5,
+            seed: 8,
+            players: record.players.clone(),
+            winner: Some(game::Player::X),
+            forfeit: Some(game::Player::O),
+            illegal_moves: vec![
+                (game::Player::O, "9".to_string()),
+                (game::Player::O, "0".to_string()),
+            ],
+            moves: vec!["0".to_string()],
+        };
+
+        let text = format!("{record}\n{draw}\n{forfeit}");
+        assert_eq!(
+            text.lines().nth(8),
+            Some("Moves: 0 4 1 2 8 6"),
+            "The moves should be separated by spaces"
+        );
+        assert!(text.contains("Forfeit: O\nIllegal moves: O 9, O 0\n"));
+        assert_eq!(parse_records(&text), Ok(vec![record, draw, forfeit]));
+
+        assert!(parse_records("Game: tic-tac-toe\nIndex: 0").is_err());
+        assert!("Game: tic-tac-toe\nSeed: 0".parse::<Record>().is_err());
+    }
+}
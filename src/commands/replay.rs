@@ -0,0 +1,92 @@
+use std::{fs, path::Path};
+
+use crate::{
+    game::{Game, Status},
+    ReLearnError,
+};
+
+use super::record::parse_records;
+
+/// Prints every state of a recorded game, the game with the given `index` or the first one in the
+/// file, then the illegal moves and the recorded result.
+pub(crate) fn replay<G: Game>(
+    game: &G,
+    path: &Path,
+    index: Option<u32>,
+) -> Result<(), ReLearnError> {
+    let text =
+        fs::read_to_string(path).map_err(|err| ReLearnError::InvalidRecord(err.to_string()))?;
+    let records = parse_records(&text).map_err(ReLearnError::InvalidRecord)?;
+
+    let record = match index {
+        Some(index) => records.iter().find(|record| record.index == index),
+        None => records.first(),
+    }
+    .ok_or_else(|| ReLearnError::InvalidRecord("The game is not in the file".to_string()))?;
+
+    if record.game != game.name() {
+        return Err(ReLearnError::InvalidRecord(format!(
+            "The record is a game of {} but the game is {}, did you forget the '--game' option?",
+            record.game,
+            game.name()
+        )));
+    }
+
+    println!(
+        "Game {}: {} (X) vs {} (O), seed {}",
+        record.index, record.players[0], record.players[1], record.seed
+    );
+
+    let mut state = game.initial_state();
+    println!("{state}");
+
+    // The chance events are not numbered, so the moves are numbered as the decisions of the players
+    let mut move_number = 0;
+
+    for (number, action) in record.moves.iter().enumerate() {
+        let player = game.current_player(&state);
+        let chance_event = !game.chance_events(&state).is_empty();
+        let invalid_move = |error: String| {
+            ReLearnError::InvalidRecord(format!("Invalid move {} '{action}': {error}", number + 1))
+        };
+
+        let parsed_action = action
+            .parse()
+            .map_err(|_| invalid_move("it could not be parsed".to_string()))?;
+        game.act(player, parsed_action, &mut state)
+            .map_err(|err| invalid_move(err.kind.to_string()))?;
+
+        if chance_event {
+            println!("Chance event: {action}");
+        } else {
+            move_number += 1;
+            println!("Move {move_number}: {player} plays {action}");
+        }
+        println!("{state}");
+    }
+
+    // The games given up end before the moves do
+    if record.forfeit.is_none() && game.status(&state) != Status::Finished(record.winner) {
+        return Err(ReLearnError::InvalidRecord(
+            "The moves don't lead to the recorded result".to_string(),
+        ));
+    }
+
+    if !record.illegal_moves.is_empty() {
+        let illegal_moves: Vec<_> = record
+            .illegal_moves
+            .iter()
+            .map(|(player, action)| format!("{player} tried {action}"))
+            .collect();
+
+        println!("Illegal moves: {}", illegal_moves.join(", "));
+    }
+
+    match (record.winner, record.forfeit) {
+        (Some(winner), Some(forfeit)) => println!("Status: {winner} won, {forfeit} forfeited"),
+        (Some(winner), None) => println!("Status: {winner} won"),
+        (None, _) => println!("Status: Draw"),
+    }
+
+    Ok(())
+}
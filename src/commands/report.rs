@@ -0,0 +1,383 @@
+use std::{collections::BTreeMap, sync::LazyLock, time::Duration};
+
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{game::Game, players::Player, ReLearnError};
+
+use super::{
+    play::{GamesResult, IllegalMove, SeatResult},
+    statistics::{elo_difference, score_interval, wilson_interval, Hypothesis, Sprt},
+};
+
+#[derive(Clone, Copy, ValueEnum)]
+pub(crate) enum Format {
+    /// A human readable summary
+    Text,
+    Json,
+    /// A header and a single row, the nested fields are joined by dots (e.g., `total.victories`)
+    Csv,
+}
+
+/// Every parameter of the agents (see `Player::parameters`), the CSV reports have a column for each
+/// one.
+const PARAMETERS: [&str; 8] = [
+    "depth",
+    "discount",
+    "episodes",
+    "epsilon_end",
+    "epsilon_start",
+    "exploration",
+    "iterations",
+    "learning_rate",
+];
+
+/// The name and the parameters of a player.
+#[derive(Serialize)]
+pub(crate) struct PlayerDescription {
+    pub(crate) name: String,
+    /// Named as in `PARAMETERS`.
+    pub(crate) parameters: BTreeMap<&'static str, f64>,
+}
+
+impl PlayerDescription {
+    pub(crate) fn new<G: Game>(name: String, player: &dyn Player<G>) -> Self {
+        PlayerDescription {
+            name,
+            parameters: player.parameters().into_iter().collect(),
+        }
+    }
+}
+
+/// Everything we know about a match, for other programs to consume. The results are from the
+/// point of view of the first player.
+#[derive(Serialize)]
+pub(super) struct Report<'a> {
+    game: String,
+    player_1: &'a PlayerDescription,
+    player_2: &'a PlayerDescription,
+    as_x: SeatResult,
+    as_o: SeatResult,
+    total: SeatResult,
+    win_rate: Interval,
+    score: Interval,
+    elo_difference: Interval,
+    /// How many games ended after each number of moves, indexed by the number of moves.
+    game_lengths: &'a [u32],
+    average_game_length: f64,
+    illegal_moves: PerPlayer<u32>,
+    forfeits: PerPlayer<u32>,
+    first_illegal_moves: PerPlayer<Option<&'a IllegalMove>>,
+    elapsed_seconds: f64,
+    seed: u64,
+    sprt: Option<SprtReport>,
+}
+
+/// An estimate and its 95% confidence interval.
+#[derive(Clone, Copy, Serialize)]
+struct Interval {
+    estimate: f64,
+    lower: f64,
+    upper: f64,
+}
+
+#[derive(Clone, Copy, Serialize)]
+struct PerPlayer<T> {
+    player_1: T,
+    player_2: T,
+}
+
+#[derive(Serialize)]
+struct SprtReport {
+    #[serde(flatten)]
+    parameters: Sprt,
+    log_likelihood_ratio: f64,
+    lower_bound: f64,
+    upper_bound: f64,
+    decision: Option<Hypothesis>,
+}
+
+impl<'a> Report<'a> {
+    pub(super) fn new<G: Game>(
+        game: &G,
+        players: &'a [PlayerDescription; 2],
+        games_result: &'a GamesResult,
+        sprt: Option<&Sprt>,
+        elapsed: Duration,
+        seed: u64,
+    ) -> Self {
+        let total = games_result.total();
+
+        let (lower, upper) = wilson_interval(total.victories, total.game_count());
+        let win_rate = Interval {
+            estimate: total.victories as f64 / total.game_count().max(1) as f64,
+            lower,
+            upper,
+        };
+
+        let (score, margin) = score_interval(&total);
+        let score = Interval {
+            estimate: score,
+            lower: (score - margin).max(0.0),
+            upper: (score + margin).min(1.0),
+        };
+        let elo_difference = Interval {
+            estimate: elo_difference(score.estimate),
+            lower: elo_difference(score.lower),
+            upper: elo_difference(score.upper),
+        };
+
+        let sprt = sprt.map(|sprt| {
+            let (lower_bound, upper_bound) = sprt.bounds();
+
+            SprtReport {
+                parameters: *sprt,
+                log_likelihood_ratio: sprt.log_likelihood_ratio(&total),
+                lower_bound,
+                upper_bound,
+                decision: sprt.decision(&total),
+            }
+        });
+
+        Report {
+            game: game.name(),
+            player_1: &players[0],
+            player_2: &players[1],
+            as_x: games_result.as_x,
+            as_o: games_result.as_o,
+            total,
+            win_rate,
+            score,
+            elo_difference,
+            game_lengths: &games_result.lengths,
+            average_game_length: games_result.average_length(),
+            illegal_moves: PerPlayer::new(games_result.illegal_moves),
+            forfeits: PerPlayer::new(games_result.forfeits),
+            first_illegal_moves: PerPlayer::new(
+                games_result
+                    .first_illegal_moves
+                    .each_ref()
+                    .map(Option::as_ref),
+            ),
+            elapsed_seconds: elapsed.as_secs_f64(),
+            seed,
+            sprt,
+        }
+    }
+
+    /// NOTE: JSON has no infinity, so the infinite Elo differences of perfect scores are `null`.
+    pub(super) fn to_json(&self) -> Result<String, ReLearnError> {
+        serde_json::to_string_pretty(self)
+            .map(|json| json + "\n")
+            .map_err(|err| ReLearnError::OutputError(err.to_string()))
+    }
+
+    /// The columns are the same for every match, so the reports can be joined in a single table.
+    /// The missing fields (e.g., the `sprt` of the matches without one) have empty cells.
+    pub(super) fn to_csv(&self) -> Result<String, ReLearnError> {
+        let cells: BTreeMap<_, _> = self.columns()?.into_iter().collect();
+
+        let (header, row): (Vec<_>, Vec<_>) = self
+            .template()
+            .columns()?
+            .into_iter()
+            .map(|(name, _)| {
+                let cell = cells.get(&name).map_or("", String::as_str);
+                (escape(&name), escape(cell))
+            })
+            .unzip();
+
+        Ok(format!("{}\n{}\n", header.join(","), row.join(",")))
+    }
+
+    fn columns(&self) -> Result<Vec<(String, String)>, ReLearnError> {
+        let value =
+            serde_json::to_value(self).map_err(|err| ReLearnError::OutputError(err.to_string()))?;
+
+        let mut columns = Vec::new();
+        flatten(String::new(), &value, &mut columns);
+
+        Ok(columns)
+    }
+
+    /// The report with every optional field filled, its columns are the ones of all the CSV reports.
+    fn template(&self) -> Report<'static> {
+        static PLAYER: LazyLock<PlayerDescription> = LazyLock::new(|| PlayerDescription {
+            name: String::new(),
+            parameters: PARAMETERS
+                .into_iter()
+                .map(|parameter| (parameter, 0.0))
+                .collect(),
+        });
+        static ILLEGAL_MOVE: IllegalMove = IllegalMove {
+            game_index: 0,
+            description: String::new(),
+        };
+
+        Report {
+            game: String::new(),
+            player_1: &PLAYER,
+            player_2: &PLAYER,
+            game_lengths: &[],
+            first_illegal_moves: PerPlayer::new([Some(&ILLEGAL_MOVE); 2]),
+            sprt: Some(SprtReport {
+                parameters: Sprt::default(),
+                log_likelihood_ratio: 0.0,
+                lower_bound: 0.0,
+                upper_bound: 0.0,
+                decision: None,
+            }),
+            ..*self
+        }
+    }
+}
+
+impl<T> PerPlayer<T> {
+    fn new([player_1, player_2]: [T; 2]) -> Self {
+        PerPlayer { player_1, player_2 }
+    }
+}
+
+/// Turns the nested fields into columns, named by the path to the field.
+fn flatten(name: String, value: &Value, columns: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(fields) => {
+            for (key, value) in fields {
+                let name = if name.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{name}.{key}")
+                };
+
+                flatten(name, value, columns);
+            }
+        }
+        // The arrays only hold numbers, so they fit in a single cell
+        Value::Array(values) => {
+            let values: Vec<_> = values.iter().map(Value::to_string).collect();
+            columns.push((name, values.join(" ")));
+        }
+        Value::String(string) => columns.push((name, string.clone())),
+        Value::Null => columns.push((name, String::new())),
+        Value::Bool(_) | Value::Number(_) => columns.push((name, value.to_string())),
+    }
+}
+
+fn escape(cell: &str) -> String {
+    if cell.contains([',', '"', '\n']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::TicTacToe;
+
+    #[test]
+    fn test_to_csv() {
+        let players = [
+            PlayerDescription {
+                name: "alpha-beta".to_string(),
+                parameters: BTreeMap::from([("depth", 4.0)]),
+            },
+            PlayerDescription {
+                name: "random".to_string(),
+                parameters: BTreeMap::new(),
+            },
+        ];
+        let games_result = GamesResult {
+            as_x: SeatResult {
+                victories: 2,
+                draws: 0,
+                losses: 0,
+            },
+            as_o: SeatResult {
+                victories: 1,
+                draws: 1,
+                losses: 0,
+            },
+            lengths: vec![0, 0, 0, 0, 0, 2, 0, 1, 0, 1],
+            illegal_moves: [0, 3],
+            forfeits: [0, 1],
+            first_illegal_moves: [
+                None,
+                Some(IllegalMove {
+                    game_index: 2,
+                    description: "Move 9: the move is out of the board".to_string(),
+                }),
+            ],
+        };
+
+        let report = Report::new(
+            &TicTacToe::default(),
+            &players,
+            &games_result,
+            None,
+            Duration::from_secs(2),
+            42,
+        );
+        let csv = report.to_csv().unwrap();
+        let (header, row) = csv.trim_end().split_once('\n').unwrap();
+        let columns: BTreeMap<_, _> = header.split(',').zip(row.split(',')).collect();
+
+        assert_eq!(columns["game"], "tic-tac-toe");
+        assert_eq!(columns["player_1.name"], "alpha-beta");
+        assert_eq!(columns["player_1.parameters.depth"], "4.0");
+        assert_eq!(columns["total.victories"], "3");
+        assert_eq!(columns["as_o.draws"], "1");
+        assert_eq!(columns["game_lengths"], "0 0 0 0 0 2 0 1 0 1");
+        assert_eq!(columns["average_game_length"], "6.5");
+        assert_eq!(columns["illegal_moves.player_2"], "3");
+        assert_eq!(columns["forfeits.player_2"], "1");
+        assert_eq!(columns["first_illegal_moves.player_1.game_index"], "");
+        assert_eq!(columns["first_illegal_moves.player_2.game_index"], "2");
+        assert_eq!(columns["elapsed_seconds"], "2.0");
+        assert_eq!(columns["seed"], "42");
+        assert_eq!(columns["player_1.parameters.iterations"], "");
+        assert_eq!(columns["player_2.parameters.learning_rate"], "");
+        assert_eq!(columns["sprt.elo1"], "");
+        assert_eq!(columns["sprt.decision"], "");
+
+        assert_eq!(escape("a,\"b\""), "\"a,\"\"b\"\"\"");
+
+        // The matches with a SPRT, other players and no illegal moves have the same columns
+        let sprt: Sprt = "0,50,0.05,0.05".parse().unwrap();
+        let other_players = [
+            PlayerDescription {
+                name: "mcts".to_string(),
+                parameters: BTreeMap::from([("iterations", 1000.0), ("exploration", 1.4)]),
+            },
+            PlayerDescription {
+                name: "min-max".to_string(),
+                parameters: BTreeMap::new(),
+            },
+        ];
+        let other_games_result = GamesResult {
+            as_x: games_result.as_x,
+            ..GamesResult::default()
+        };
+        let other_csv = Report::new(
+            &TicTacToe::default(),
+            &other_players,
+            &other_games_result,
+            Some(&sprt),
+            Duration::from_secs(1),
+            7,
+        )
+        .to_csv()
+        .unwrap();
+        let (other_header, other_row) = other_csv.trim_end().split_once('\n').unwrap();
+        let other_columns: BTreeMap<_, _> =
+            other_header.split(',').zip(other_row.split(',')).collect();
+
+        assert_eq!(header, other_header);
+        assert_eq!(other_columns["player_1.parameters.depth"], "");
+        assert_eq!(other_columns["player_1.parameters.iterations"], "1000.0");
+        assert_eq!(other_columns["sprt.elo1"], "50.0");
+        assert_eq!(other_columns["first_illegal_moves.player_2.game_index"], "");
+    }
+}
@@ -0,0 +1,56 @@
+use fastrand::Rng;
+
+use crate::{
+    game::{self, Game, IllegalMovePolicy},
+    players::{read_line, Player},
+};
+
+/// Plays one game after the other between a human and an agent in the main thread, the human
+/// starts the first game and then they alternate. After each game the running score is shown and
+/// the human chooses whether to play again.
+pub(crate) fn play_human<G: Game>(
+    game: &G,
+    human: &dyn Player<G>,
+    opponent: &dyn Player<G>,
+    opponent_name: &str,
+    seed: u64,
+) {
+    let mut rng = Rng::with_seed(seed);
+    let (mut victories, mut draws, mut losses) = (0, 0, 0);
+
+    for index in 0_u32.. {
+        let human_seat = if index.is_multiple_of(2) {
+            game::Player::X
+        } else {
+            game::Player::O
+        };
+
+        println!(
+            "Game {}: you play {human_seat} against {opponent_name}",
+            index + 1
+        );
+
+        // The human never plays an illegal move, so only the agents may forfeit
+        let record = match human_seat {
+            game::Player::X => game.play(human, opponent, IllegalMovePolicy::Forfeit, &mut rng),
+            game::Player::O => game.play(opponent, human, IllegalMovePolicy::Forfeit, &mut rng),
+        };
+
+        match record.winner {
+            Some(winner) if winner == human_seat => victories += 1,
+            Some(_) => losses += 1,
+            None => draws += 1,
+        }
+
+        println!(
+            "Score: you {victories}, {opponent_name} {losses}, draws {draws}\nPlay again? [Y/n]"
+        );
+
+        match read_line() {
+            Some(answer) if answer.is_empty() || answer.eq_ignore_ascii_case("y") => {}
+            _ => break,
+        }
+    }
+
+    println!("Seed: {seed}");
+}
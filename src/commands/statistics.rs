@@ -0,0 +1,254 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::Serialize;
+
+use super::play::SeatResult;
+
+/// A difference of 400 points means that the stronger player is expected to score 10 times more
+/// than the weaker one.
+pub(super) const ELO_SCALE: f64 = 400.0;
+/// The z-score of the 95% confidence interval.
+pub(super) const Z_SCORE: f64 = 1.96;
+
+/// The 95% confidence interval of a proportion, using the Wilson score interval, which unlike the
+/// normal approximation behaves well with few trials and proportions close to 0 or 1.
+pub(super) fn wilson_interval(successes: u32, trials: u32) -> (f64, f64) {
+    if trials == 0 {
+        return (0.0, 1.0);
+    }
+
+    let trials = trials as f64;
+    let proportion = successes as f64 / trials;
+    let z2 = Z_SCORE * Z_SCORE;
+
+    let center = (proportion + z2 / (2.0 * trials)) / (1.0 + z2 / trials);
+    let margin = Z_SCORE / (1.0 + z2 / trials)
+        * (proportion * (1.0 - proportion) / trials + z2 / (4.0 * trials * trials)).sqrt();
+
+    (center - margin, center + margin)
+}
+
+/// The score (1 for a victory and 0.5 for a draw) per game and the margin of error of its 95%
+/// confidence interval, using the normal approximation.
+pub(super) fn score_interval(result: &SeatResult) -> (f64, f64) {
+    let game_count = result.game_count().max(1) as f64;
+    let score = score(result);
+
+    (
+        score,
+        Z_SCORE * (score_variance(result, score) / game_count).sqrt(),
+    )
+}
+
+/// The Elo difference that is expected to give the `score` to the stronger player.
+pub(super) fn elo_difference(score: f64) -> f64 {
+    -ELO_SCALE * (1.0 / score - 1.0).log10()
+}
+
+/// The expected score of a player rated `elo` points above its opponent.
+fn expected_score(elo: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-elo / ELO_SCALE))
+}
+
+fn score(result: &SeatResult) -> f64 {
+    (result.victories as f64 + result.draws as f64 / 2.0) / result.game_count().max(1) as f64
+}
+
+/// The variance of the score of a single game.
+fn score_variance(result: &SeatResult, score: f64) -> f64 {
+    (result.victories as f64 * (1.0 - score).powi(2)
+        + result.draws as f64 * (0.5 - score).powi(2)
+        + result.losses as f64 * score.powi(2))
+        / result.game_count().max(1) as f64
+}
+
+/// The Sequential Probability Ratio Test, it plays games until there is enough evidence to decide
+/// whether the first player is `elo0` (the null hypothesis) or `elo1` (the alternative hypothesis)
+/// points stronger than the second player.
+///
+/// `alpha` is the probability of accepting `elo1` when `elo0` is true (false positive) and `beta`
+/// is the probability of accepting `elo0` when `elo1` is true (false negative).
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+pub(crate) struct Sprt {
+    elo0: f64,
+    elo1: f64,
+    alpha: f64,
+    beta: f64,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub(super) enum Hypothesis {
+    /// The player is `elo0` points stronger.
+    #[serde(rename = "H0")]
+    Null,
+    /// The player is `elo1` points stronger.
+    #[serde(rename = "H1")]
+    Alternative,
+}
+
+impl Sprt {
+    /// The log-likelihood ratio of the hypotheses, using the normal approximation of the
+    /// generalized SPRT.
+    ///
+    /// The variance of the score is 0 while all the games have the same result, which would stop
+    /// the most decisive matches from ever being decided. So, like the tournament ratings, we add a
+    /// virtual draw, which counts as half a victory and half a loss to give a variance to a run of
+    /// draws too. The results are doubled to count the halves.
+    pub(super) fn log_likelihood_ratio(&self, result: &SeatResult) -> f64 {
+        let regularized = SeatResult {
+            victories: 2 * result.victories + 1,
+            draws: 2 * result.draws,
+            losses: 2 * result.losses + 1,
+        };
+        let score = score(&regularized);
+        let variance = score_variance(&regularized, score);
+
+        let (score0, score1) = (expected_score(self.elo0), expected_score(self.elo1));
+
+        result.game_count() as f64 * (score1 - score0) * (2.0 * score - score0 - score1)
+            / (2.0 * variance)
+    }
+
+    /// The log-likelihood ratio below which the null hypothesis is accepted and above which the
+    /// alternative hypothesis is accepted.
+    pub(super) fn bounds(&self) -> (f64, f64) {
+        (
+            (self.beta / (1.0 - self.alpha)).ln(),
+            ((1.0 - self.beta) / self.alpha).ln(),
+        )
+    }
+
+    pub(super) fn decision(&self, result: &SeatResult) -> Option<Hypothesis> {
+        let log_likelihood_ratio = self.log_likelihood_ratio(result);
+        let (lower, upper) = self.bounds();
+
+        if log_likelihood_ratio <= lower {
+            Some(Hypothesis::Null)
+        } else if log_likelihood_ratio >= upper {
+            Some(Hypothesis::Alternative)
+        } else {
+            None
+        }
+    }
+}
+
+impl FromStr for Sprt {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || {
+            format!(
+                "Invalid SPRT '{s}', expected the '<ELO0>,<ELO1>,<ALPHA>,<BETA>' format with \
+                ELO0 < ELO1 and ALPHA and BETA between 0 and 1"
+            )
+        };
+
+        let values = s
+            .split(',')
+            .map(|value| value.trim().parse::<f64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| error())?;
+
+        let [elo0, elo1, alpha, beta] = values[..] else {
+            return Err(error());
+        };
+
+        let is_probability = |value: f64| value > 0.0 && value < 1.0;
+
+        if elo0 >= elo1 || !is_probability(alpha) || !is_probability(beta) {
+            return Err(error());
+        }
+
+        Ok(Sprt {
+            elo0,
+            elo1,
+            alpha,
+            beta,
+        })
+    }
+}
+
+impl Display for Sprt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "elo0: {}, elo1: {}, alpha: {}, beta: {}",
+            self.elo0, self.elo1, self.alpha, self.beta
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_intervals() {
+        let (lower, upper) = wilson_interval(5, 10);
+        assert!((lower - 0.2366).abs() < 1e-4);
+        assert!((upper - 0.7634).abs() < 1e-4);
+
+        // The interval never leaves [0, 1]
+        let (lower, upper) = wilson_interval(10, 10);
+        assert!(lower > 0.5 && (upper - 1.0).abs() < 1e-9);
+
+        let result = SeatResult {
+            victories: 60,
+            draws: 30,
+            losses: 10,
+        };
+        let (score, margin) = score_interval(&result);
+        assert!((score - 0.75).abs() < 1e-9);
+        assert!(margin > 0.0 && margin < 0.1);
+
+        // A 75% score is expected from a player rated ~191 points above its opponent
+        assert!((elo_difference(score) - 190.85).abs() < 0.01);
+        assert_eq!(elo_difference(0.5), 0.0);
+    }
+
+    #[test]
+    fn test_sprt() {
+        let sprt: Sprt = "0,10,0.05,0.05".parse().unwrap();
+        let (lower, upper) = sprt.bounds();
+        assert!((lower + 2.944).abs() < 1e-3);
+        assert!((upper - 2.944).abs() < 1e-3);
+
+        let winning = SeatResult {
+            victories: 60,
+            draws: 30,
+            losses: 10,
+        };
+        assert_eq!(sprt.decision(&winning), Some(Hypothesis::Alternative));
+
+        let losing = SeatResult {
+            victories: 10,
+            draws: 30,
+            losses: 60,
+        };
+        assert_eq!(sprt.decision(&losing), Some(Hypothesis::Null));
+
+        let even = SeatResult {
+            victories: 1,
+            draws: 0,
+            losses: 1,
+        };
+        assert_eq!(sprt.decision(&even), None);
+
+        // The matches where every game has the same result stop early too
+        let decision_after = |victories, draws, losses| {
+            sprt.decision(&SeatResult {
+                victories,
+                draws,
+                losses,
+            })
+        };
+        assert_eq!(decision_after(0, 0, 0), None);
+        assert!((1..30).any(|games| decision_after(games, 0, 0) == Some(Hypothesis::Alternative)));
+        assert!((1..30).any(|games| decision_after(0, 0, games) == Some(Hypothesis::Null)));
+        assert!((1..500).any(|games| decision_after(0, games, 0) == Some(Hypothesis::Null)));
+
+        assert!("10,0,0.05,0.05".parse::<Sprt>().is_err());
+        assert!("0,10,0.05".parse::<Sprt>().is_err());
+        assert!("0,10,0,0.05".parse::<Sprt>().is_err());
+    }
+}
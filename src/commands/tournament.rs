@@ -0,0 +1,204 @@
+use crate::{
+    game::{Game, IllegalMovePolicy},
+    players::Player,
+};
+
+use super::{
+    play::{derive_seed, play_match},
+    statistics::{ELO_SCALE, Z_SCORE},
+};
+
+const AVERAGE_RATING: f64 = 1500.0;
+const ITERATIONS: usize = 1000;
+
+/// The Elo rating of a player and the margin of error of its 95% confidence interval.
+struct Rating {
+    elo: f64,
+    margin: f64,
+}
+
+/// Plays a round-robin tournament, where every player plays `game_count` games against each one
+/// of the others (half of them starting), then prints the crosstable and the Elo ratings.
+///
+/// Each pairing is a match with its own seed, derived from the `seed` of the tournament, so the
+/// pairings don't play the same random games.
+pub(crate) fn tournament<G: Game>(
+    game: &G,
+    players: &[(String, Box<dyn Player<G>>)],
+    game_count: u32,
+    seed: u64,
+    policy: IllegalMovePolicy,
+) {
+    let player_count = players.len();
+
+    // The points (1 for a victory and 0.5 for a draw) of each player against each other player
+    let mut scores = vec![vec![0.0; player_count]; player_count];
+    let mut games = vec![vec![0; player_count]; player_count];
+
+    let pairings = (0..player_count).flat_map(|i| (i + 1..player_count).map(move |j| (i, j)));
+
+    for (pairing, (i, j)) in pairings.enumerate() {
+        let result = play_match(
+            game,
+            [players[i].1.as_ref(), players[j].1.as_ref()],
+            game_count,
+            None,
+            None,
+            derive_seed(seed, pairing as u32),
+            policy,
+        )
+        .total();
+        let draws = result.draws as f64 / 2.0;
+
+        scores[i][j] = result.victories as f64 + draws;
+        scores[j][i] = result.losses as f64 + draws;
+        games[i][j] = result.game_count();
+        games[j][i] = games[i][j];
+    }
+
+    let name_width = players
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or_default()
+        .max(6);
+
+    println!("Crosstable (score of the row player against the column player):");
+    print!("{:name_width$}", "");
+    for (name, _) in players {
+        print!(" {name:>name_width$}");
+    }
+    println!();
+
+    for (i, (name, _)) in players.iter().enumerate() {
+        print!("{name:name_width$}");
+
+        for j in 0..player_count {
+            if i == j {
+                print!(" {:>name_width$}", "-");
+            } else {
+                let score = 100.0 * scores[i][j] / games[i][j] as f64;
+                print!(" {:>name_width$}", format!("{score:.1}%"));
+            }
+        }
+
+        println!();
+    }
+
+    let ratings = elo_ratings(&scores, &games);
+    let mut ranking: Vec<_> = players.iter().zip(ratings).collect();
+    ranking.sort_by(|(_, a), (_, b)| b.elo.total_cmp(&a.elo));
+
+    println!();
+    println!("Ratings (95% confidence interval):");
+
+    for (position, ((name, _), rating)) in ranking.iter().enumerate() {
+        println!(
+            "{:>3}. {name:name_width$} {:>6.0} ± {:.0}",
+            position + 1,
+            rating.elo,
+            rating.margin
+        );
+    }
+
+    println!();
+    println!("Seed: {seed}");
+}
+
+/// Estimates the ratings that best explain the scores (the maximum likelihood of the Bradley-Terry
+/// model), using the minorization-maximization algorithm.
+///
+/// A player that won all of its games would have an infinite rating, so we add a virtual draw
+/// between every pair of players.
+fn elo_ratings(scores: &[Vec<f64>], games: &[Vec<u32>]) -> Vec<Rating> {
+    let player_count = scores.len();
+    let pairs = || {
+        (0..player_count).flat_map(|i| {
+            (0..player_count)
+                .filter(move |&j| i != j && games[i][j] > 0)
+                .map(move |j| (i, j))
+        })
+    };
+
+    let mut strengths = vec![1.0; player_count];
+
+    for _ in 0..ITERATIONS {
+        let mut points = vec![0.0; player_count];
+        let mut denominators = vec![0.0; player_count];
+
+        for (i, j) in pairs() {
+            points[i] += scores[i][j] + 0.5;
+            denominators[i] += (games[i][j] + 1) as f64 / (strengths[i] + strengths[j]);
+        }
+
+        for i in 0..player_count {
+            if denominators[i] > 0.0 {
+                strengths[i] = points[i] / denominators[i];
+            }
+        }
+
+        // The ratings are relative, so we keep the geometric mean of the strengths at 1, which
+        // puts the average rating at `AVERAGE_RATING`.
+        let log_mean =
+            strengths.iter().map(|strength| strength.ln()).sum::<f64>() / player_count as f64;
+        strengths
+            .iter_mut()
+            .for_each(|strength| *strength /= log_mean.exp());
+    }
+
+    // The standard error comes from the Fisher information of the rating
+    let mut information = vec![0.0; player_count];
+
+    for (i, j) in pairs() {
+        let expected_score = strengths[i] / (strengths[i] + strengths[j]);
+        information[i] += games[i][j] as f64 * expected_score * (1.0 - expected_score);
+    }
+
+    strengths
+        .iter()
+        .zip(information)
+        .map(|(strength, information)| Rating {
+            elo: AVERAGE_RATING + ELO_SCALE * strength.log10(),
+            margin: Z_SCORE * ELO_SCALE / std::f64::consts::LN_10 / information.sqrt(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_elo_ratings() {
+        let ratings = elo_ratings(
+            &[vec![0.0, 50.0], vec![50.0, 0.0]],
+            &[vec![0, 100], vec![100, 0]],
+        );
+
+        assert!((ratings[0].elo - AVERAGE_RATING).abs() < 1e-6);
+        assert!((ratings[1].elo - AVERAGE_RATING).abs() < 1e-6);
+
+        // A 75% score is expected from a player rated ~191 points above its opponent
+        let ratings = elo_ratings(
+            &[vec![0.0, 750.0], vec![250.0, 0.0]],
+            &[vec![0, 1000], vec![1000, 0]],
+        );
+
+        assert!((ratings[0].elo - ratings[1].elo - 191.0).abs() < 1.0);
+        assert!(ratings[0].margin > 0.0 && ratings[0].margin < 50.0);
+
+        // Perfect scores still have finite ratings
+        let ratings = elo_ratings(
+            &[
+                vec![0.0, 10.0, 10.0],
+                vec![0.0, 0.0, 5.0],
+                vec![0.0, 5.0, 0.0],
+            ],
+            &[vec![0, 10, 10], vec![10, 0, 10], vec![10, 10, 0]],
+        );
+
+        assert!(ratings[0].elo.is_finite());
+        assert!(ratings[0].elo > ratings[1].elo);
+        assert!((ratings[1].elo - ratings[2].elo).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,470 @@
+use std::fmt::Display;
+
+use super::{parse_fields, Game, MoveError, MoveErrorKind, Player, Status};
+
+const WIDTH: u8 = 7;
+const HEIGHT: u8 = 6;
+/// Each column has an extra (always empty) bit on top, it's used as a separator so the shifts
+/// used in the win detection don't wrap around from one column to the next.
+const COLUMN_BITS: u8 = HEIGHT + 1;
+/// The first field of every column.
+const BOTTOM: u64 = {
+    let mut mask = 0;
+    let mut column = 0;
+    while column < WIDTH {
+        mask |= 1 << (column * COLUMN_BITS);
+        column += 1;
+    }
+    mask
+};
+
+/// The value of an open line (used by the heuristic) by the number of pieces in it.
+const LINE_VALUES: [i64; 5] = [0, 1, 3, 9, 0];
+
+/// Connect Four, a 7x6 board where the pieces are dropped from the top of a column and fall until
+/// they reach the lowest empty field. The first player to line up four pieces wins.
+pub struct ConnectFour;
+
+/// The board is stored as one bitboard per player, where the field at `column` and `row` (counting
+/// from the bottom) is the bit `column * 7 + row`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct State {
+    boards: [u64; 2],
+    moves: u8,
+}
+
+impl Game for ConnectFour {
+    type State = State;
+    type Action = u8;
+
+    fn initial_state(&self) -> State {
+        State::new()
+    }
+
+    fn status(&self, state: &State) -> Status {
+        if State::has_won(state.boards[Player::X as usize]) {
+            Status::Finished(Some(Player::X))
+        } else if State::has_won(state.boards[Player::O as usize]) {
+            Status::Finished(Some(Player::O))
+        } else if state.moves == WIDTH * HEIGHT {
+            Status::Finished(None)
+        } else {
+            Status::OnGoing
+        }
+    }
+
+    fn available_moves(&self, state: &State) -> Vec<u8> {
+        (0..WIDTH)
+            .filter(|&column| state.can_play(column))
+            .collect()
+    }
+
+    fn act(&self, player: Player, column: u8, state: &mut State) -> Result<(), MoveError<Self>> {
+        state
+            .act(player, column)
+            .map_err(|kind| MoveError::new(kind, column, state))
+    }
+
+    fn current_player(&self, state: &State) -> Player {
+        if state.moves.is_multiple_of(2) {
+            Player::X
+        } else {
+            Player::O
+        }
+    }
+
+    fn parse_state(&self, board: &str) -> Result<State, String> {
+        let fields = parse_fields(board, WIDTH as usize, HEIGHT as usize)?;
+
+        // SAFETY: The fields were parsed from a 7x6 board
+        let state = State::from_array(unsafe { fields.try_into().unwrap_unchecked() });
+
+        // The pieces fall until the lowest empty field, so the fields of each column are filled
+        // from the bottom, without gaps.
+        for column in 0..WIDTH {
+            let bottom = State::field(column, 0);
+            let column = state.mask() & State::column(column);
+
+            // Adding the bottom field carries until the first empty field, clearing all the pieces
+            if (column + bottom) & column != 0 {
+                return Err("The pieces can't float over empty fields".to_string());
+            }
+        }
+
+        Ok(state)
+    }
+
+    fn encode(&self, state: &State) -> u64 {
+        // Adding `BOTTOM` to the mask sets the first empty field of every column and clears all
+        // the fields below it, so the highest bit of each column marks its height and the bits below
+        // it tell which pieces belong to `Player::X`. It fits in 49 bits and it's unique.
+        state.boards[Player::X as usize] + state.mask() + BOTTOM
+    }
+
+    fn name(&self) -> String {
+        "connect-four".to_string()
+    }
+
+    /// Counts the lines of four fields that are still open for each player, the more pieces a line
+    /// already has, the more it's worth.
+    fn heuristic(&self, state: &State, player: Player) -> i64 {
+        let player_board = state.boards[player as usize];
+        let opponent_board = state.boards[player.next_player() as usize];
+
+        let mut value = 0;
+
+        for line in State::lines() {
+            if line & opponent_board == 0 {
+                value += LINE_VALUES[(line & player_board).count_ones() as usize];
+            } else if line & player_board == 0 {
+                value -= LINE_VALUES[(line & opponent_board).count_ones() as usize];
+            }
+        }
+
+        value
+    }
+}
+
+impl State {
+    pub(crate) fn new() -> Self {
+        State {
+            boards: [0, 0],
+            moves: 0,
+        }
+    }
+
+    /// Builds the state from the fields, from the top row to the bottom one. It does not check if
+    /// the pieces are floating.
+    fn from_array(fields: [Option<Player>; (WIDTH * HEIGHT) as usize]) -> Self {
+        let mut state = State::new();
+
+        for (idx, field) in fields.iter().enumerate() {
+            if let Some(player) = field {
+                let column = idx as u8 % WIDTH;
+                let row = HEIGHT - 1 - idx as u8 / WIDTH;
+
+                state.boards[*player as usize] |= State::field(column, row);
+                state.moves += 1;
+            }
+        }
+
+        state
+    }
+
+    fn act(&mut self, player: Player, column: u8) -> Result<(), MoveErrorKind> {
+        if column >= WIDTH {
+            return Err(MoveErrorKind::OutOfBound);
+        }
+
+        if !self.can_play(column) {
+            return Err(MoveErrorKind::NonEmptyField);
+        }
+
+        // Adding the bottom field of the column to the mask carries the bit until the first empty
+        // field in the column.
+        let mask = self.mask();
+        let field = (mask + State::field(column, 0)) & State::column(column);
+
+        self.boards[player as usize] |= field;
+        self.moves += 1;
+
+        Ok(())
+    }
+
+    fn can_play(&self, column: u8) -> bool {
+        self.mask() & State::field(column, HEIGHT - 1) == 0
+    }
+
+    fn mask(&self) -> u64 {
+        self.boards[0] | self.boards[1]
+    }
+
+    fn field(column: u8, row: u8) -> u64 {
+        1 << (column * COLUMN_BITS + row)
+    }
+
+    fn column(column: u8) -> u64 {
+        ((1 << HEIGHT) - 1) << (column * COLUMN_BITS)
+    }
+
+    fn get(&self, column: u8, row: u8) -> Option<Player> {
+        let field = State::field(column, row);
+
+        if self.boards[Player::X as usize] & field != 0 {
+            Some(Player::X)
+        } else if self.boards[Player::O as usize] & field != 0 {
+            Some(Player::O)
+        } else {
+            None
+        }
+    }
+
+    /// All the groups of four aligned fields in the board.
+    fn lines() -> impl Iterator<Item = u64> {
+        // vertical, horizontal, diagonal (/) and diagonal (\)
+        [(0, 1), (1, 0), (1, 1), (1, -1)]
+            .into_iter()
+            .flat_map(|(dx, dy): (i8, i8)| {
+                (0..WIDTH as i8).flat_map(move |column| {
+                    (0..HEIGHT as i8).filter_map(move |row| {
+                        let (last_column, last_row) = (column + 3 * dx, row + 3 * dy);
+
+                        if last_column >= WIDTH as i8 || !(0..HEIGHT as i8).contains(&last_row) {
+                            return None;
+                        }
+
+                        Some((0..4).fold(0, |line, i| {
+                            line | State::field((column + i * dx) as u8, (row + i * dy) as u8)
+                        }))
+                    })
+                })
+            })
+    }
+
+    /// Checks if there are four aligned pieces in the bitboard.
+    ///
+    /// For each direction we shift the board by the distance between two neighbour fields, so the
+    /// `AND` of the board with its shifted version keep only the pieces that have a neighbour. Doing
+    /// it again with double the distance keeps only the pieces with three neighbours.
+    fn has_won(board: u64) -> bool {
+        // vertical, horizontal, diagonal (\) and diagonal (/)
+        for direction in [1, COLUMN_BITS, COLUMN_BITS - 1, COLUMN_BITS + 1] {
+            let pairs = board & (board >> direction);
+
+            if pairs & (pairs >> (2 * direction)) != 0 {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+impl Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in (0..HEIGHT).rev() {
+            write!(f, "|")?;
+
+            for column in 0..WIDTH {
+                match self.get(column, row) {
+                    Some(player) => write!(f, " {player} |")?,
+                    None => write!(f, "   |")?,
+                }
+            }
+
+            writeln!(f)?;
+        }
+
+        write!(f, "+")?;
+        for _ in 0..WIDTH {
+            write!(f, "---+")?;
+        }
+        writeln!(f)?;
+
+        write!(f, " ")?;
+        for column in 0..WIDTH {
+            write!(f, " {column}  ")?;
+        }
+        writeln!(f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    macro_rules! state {
+        ($($s:tt)+) => {
+            State::from_array(fields![$($s)+])
+        };
+    }
+
+    #[test]
+    fn test_act() {
+        let mut state = state![
+            - - - - - - -
+            - - - - - - -
+            - - - - - - -
+            - - - - - - -
+            - - - - - - -
+            - - - X O - -
+        ];
+
+        assert!(ConnectFour.act(Player::X, 3, &mut state).is_ok());
+        assert!(ConnectFour.act(Player::O, 4, &mut state).is_ok());
+        assert!(ConnectFour.act(Player::X, 0, &mut state).is_ok());
+        assert_eq!(
+            state,
+            state![
+                - - - - - - -
+                - - - - - - -
+                - - - - - - -
+                - - - - - - -
+                - - - X O - -
+                X - - X O - -
+            ]
+        );
+        assert_eq!(
+            ConnectFour.available_moves(&state),
+            vec![0, 1, 2, 3, 4, 5, 6]
+        );
+
+        assert!(ConnectFour.act(Player::X, 7, &mut state).is_err());
+
+        let mut state = state![
+            - O - - - - -
+            - X - - - - -
+            - O - - - - -
+            - X - - - - -
+            - O - - - - -
+            - X - - - - -
+        ];
+
+        assert!(ConnectFour.act(Player::X, 1, &mut state).is_err());
+        assert_eq!(ConnectFour.available_moves(&state), vec![0, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_status() {
+        assert_eq!(
+            ConnectFour.status(&state![
+                - - - - - - -
+                - - - - - - -
+                - - - - - - -
+                - - - - - - -
+                - - - O O O -
+                - - X X X X -
+            ]),
+            Status::Finished(Some(Player::X))
+        );
+        assert_eq!(
+            ConnectFour.status(&state![
+                - - - - - - -
+                - - - - - - -
+                - - - - - - -
+                - - - - - - -
+                - - - O O O -
+                X X X - X X X
+            ]),
+            Status::OnGoing
+        );
+        assert_eq!(
+            ConnectFour.status(&state![
+                - - - - - - -
+                - - - - - - -
+                O - - - - - -
+                O - - - - - -
+                O X - - - - -
+                O X X - - - -
+            ]),
+            Status::Finished(Some(Player::O))
+        );
+        assert_eq!(
+            ConnectFour.status(&state![
+                - - - - - - -
+                - - - - - - -
+                - - - - - - X
+                - - - - - X O
+                - - - - X O O
+                - - - X O O X
+            ]),
+            Status::Finished(Some(Player::X))
+        );
+        assert_eq!(
+            ConnectFour.status(&state![
+                - - - - - - -
+                - - - - - - -
+                O - - - - - -
+                X O - - - - -
+                X X O - - - -
+                X X X O - - -
+            ]),
+            Status::Finished(Some(Player::O))
+        );
+        // The pieces don't wrap around from the top of a column to the bottom of the next one.
+        assert_eq!(
+            ConnectFour.status(&state![
+                X - - - - - -
+                X - - - - - -
+                O - - - - - -
+                O - - - - - -
+                X X - - - - -
+                O X - - - - -
+            ]),
+            Status::OnGoing
+        );
+        assert_eq!(
+            ConnectFour.status(&state![
+                X O X O X O X
+                X O X O X O X
+                O X O X O X O
+                O X O X O X O
+                X O X O X O X
+                X O X O X O X
+            ]),
+            Status::Finished(None)
+        );
+    }
+
+    #[test]
+    fn test_parse_state() {
+        let state = ConnectFour
+            .parse_state("-------|-------|-------|-------|---O---|--XXO--")
+            .unwrap();
+
+        assert_eq!(
+            state,
+            state![
+                - - - - - - -
+                - - - - - - -
+                - - - - - - -
+                - - - - - - -
+                - - - O - - -
+                - - X X O - -
+            ]
+        );
+        assert_eq!(ConnectFour.current_player(&state), Player::X);
+
+        assert!(ConnectFour
+            .parse_state("-------|-------|-------|-------|---O---|--X-X--")
+            .is_err());
+        assert!(ConnectFour
+            .parse_state("-------|-------|-------|-------|-------|--XXO--")
+            .is_ok());
+    }
+
+    #[test]
+    fn test_heuristic() {
+        assert_eq!(State::lines().count(), 69);
+
+        let state = state![
+            - - - - - - -
+            - - - - - - -
+            - - - - - - -
+            - - - - - - -
+            - - - - - - -
+            - - - X - - -
+        ];
+
+        assert!(ConnectFour.heuristic(&state, Player::X) > 0);
+        assert_eq!(
+            ConnectFour.heuristic(&state, Player::X),
+            -ConnectFour.heuristic(&state, Player::O)
+        );
+    }
+
+    #[test]
+    fn test_encode() {
+        let mut state = State::new();
+        let mut other = State::new();
+
+        assert!(ConnectFour.act(Player::X, 0, &mut state).is_ok());
+        assert!(ConnectFour.act(Player::O, 0, &mut other).is_ok());
+
+        assert_ne!(ConnectFour.encode(&state), ConnectFour.encode(&other));
+        assert_ne!(
+            ConnectFour.encode(&state),
+            ConnectFour.encode(&State::new())
+        );
+    }
+}
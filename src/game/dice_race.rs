@@ -0,0 +1,270 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Game, MoveError, MoveErrorKind, Player, Status};
+
+/// The fields of the track, the pieces start in the field `0` and they are home in the `GOAL`.
+const GOAL: u8 = 12;
+const DIE_FACES: u8 = 6;
+
+/// A race where each player rolls a die and then chooses which of its two pieces moves forward
+/// that many fields, the first player with both pieces home wins. Both players race in the same
+/// track, so a piece that lands in a field with pieces of the opponent sends them back to the
+/// start.
+///
+/// The roll is a chance event of the player to move, see `Game::chance_events`.
+pub struct DiceRace;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct State {
+    /// The field of each piece, indexed by `Player as usize`.
+    pieces: [[u8; 2]; 2],
+    player: Player,
+    /// The roll of the player to move, `None` before the die is rolled.
+    roll: Option<u8>,
+}
+
+/// Written as `roll-<N>` for the rolls and as the piece number (`1` or `2`) for the moves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Move {
+    Roll(u8),
+    /// Moves the piece forward by the roll, the pieces are numbered from 0.
+    Advance(u8),
+}
+
+impl Game for DiceRace {
+    type State = State;
+    type Action = Move;
+
+    fn initial_state(&self) -> State {
+        State {
+            pieces: [[0; 2]; 2],
+            player: Player::X,
+            roll: None,
+        }
+    }
+
+    fn status(&self, state: &State) -> Status {
+        match [Player::X, Player::O]
+            .into_iter()
+            .find(|&player| state.pieces[player as usize] == [GOAL; 2])
+        {
+            Some(winner) => Status::Finished(Some(winner)),
+            None => Status::OnGoing,
+        }
+    }
+
+    fn available_moves(&self, state: &State) -> Vec<Move> {
+        if state.roll.is_none() {
+            return Vec::new();
+        }
+
+        let [first, second] = state.pieces[state.player as usize];
+
+        // Both pieces in the same field make the same move
+        (0..2)
+            .filter(|&piece| [first, second][piece] != GOAL && (piece == 0 || first != second))
+            .map(|piece| Move::Advance(piece as u8))
+            .collect()
+    }
+
+    fn act(&self, _: Player, action: Move, state: &mut State) -> Result<(), MoveError<Self>> {
+        match (action, state.roll) {
+            (Move::Roll(roll), None) if (1..=DIE_FACES).contains(&roll) => {
+                state.roll = Some(roll);
+            }
+            (Move::Advance(piece), Some(roll)) => {
+                let player = state.player as usize;
+                let Some(&field) = state.pieces[player].get(piece as usize) else {
+                    return Err(MoveError::new(MoveErrorKind::OutOfBound, action, state));
+                };
+
+                if field == GOAL {
+                    return Err(MoveError::new(MoveErrorKind::Illegal, action, state));
+                }
+
+                let field = (field + roll).min(GOAL);
+                state.pieces[player][piece as usize] = field;
+
+                // The opponent's pieces in the field go back to the start
+                if field != GOAL {
+                    for opponent_field in &mut state.pieces[1 - player] {
+                        if *opponent_field == field {
+                            *opponent_field = 0;
+                        }
+                    }
+                }
+
+                state.player = state.player.next_player();
+                state.roll = None;
+            }
+            _ => return Err(MoveError::new(MoveErrorKind::Illegal, action, state)),
+        }
+
+        Ok(())
+    }
+
+    fn current_player(&self, state: &State) -> Player {
+        state.player
+    }
+
+    /// Each field takes 4 bits, so the whole state fits in 20 bits.
+    fn encode(&self, state: &State) -> u64 {
+        state
+            .pieces
+            .iter()
+            .flatten()
+            .fold(0, |encoded_state, &field| encoded_state << 4 | field as u64)
+            << 4
+            | (state.roll.unwrap_or_default() as u64) << 1
+            | state.player as u64
+    }
+
+    fn stochastic(&self) -> bool {
+        true
+    }
+
+    fn chance_events(&self, state: &State) -> Vec<(Move, f64)> {
+        if state.roll.is_some() || self.status(state) != Status::OnGoing {
+            return Vec::new();
+        }
+
+        (1..=DIE_FACES)
+            .map(|roll| (Move::Roll(roll), 1.0 / DIE_FACES as f64))
+            .collect()
+    }
+
+    fn name(&self) -> String {
+        "dice-race".to_string()
+    }
+
+    /// How much further the player's pieces are than the opponent's.
+    fn heuristic(&self, state: &State, player: Player) -> i64 {
+        let progress = |player: Player| {
+            state.pieces[player as usize]
+                .iter()
+                .map(|&field| field as i64)
+                .sum::<i64>()
+        };
+
+        10 * (progress(player) - progress(player.next_player()))
+    }
+}
+
+impl Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for player in [Player::X, Player::O] {
+            let [first, second] = self.pieces[player as usize].map(|field| match field {
+                0 => "start".to_string(),
+                GOAL => "home".to_string(),
+                field => field.to_string(),
+            });
+
+            writeln!(f, "{player}: piece 1 at {first}, piece 2 at {second}")?;
+        }
+
+        match self.roll {
+            Some(roll) => writeln!(f, "{} rolled {roll}", self.player),
+            None => writeln!(f, "{} rolls next", self.player),
+        }
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Move::Roll(roll) => write!(f, "roll-{roll}"),
+            Move::Advance(piece) => write!(f, "{}", piece + 1),
+        }
+    }
+}
+
+impl FromStr for Move {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("Invalid move '{s}', expected 'roll-<N>', '1' or '2'");
+
+        if let Some(roll) = s.strip_prefix("roll-") {
+            return Ok(Move::Roll(roll.parse().map_err(|_| error())?));
+        }
+
+        let piece: u8 = s.parse().map_err(|_| error())?;
+
+        Ok(Move::Advance(piece.checked_sub(1).ok_or_else(error)?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_act() {
+        let game = DiceRace;
+        let mut state = game.initial_state();
+
+        // The die is rolled before the player moves
+        assert!(game.available_moves(&state).is_empty());
+        assert!(game.act(Player::X, Move::Advance(0), &mut state).is_err());
+        assert!(game.act(Player::X, Move::Roll(7), &mut state).is_err());
+        assert!(game.act(Player::X, Move::Roll(3), &mut state).is_ok());
+        assert!(game.chance_events(&state).is_empty());
+        assert!(game.act(Player::X, Move::Roll(3), &mut state).is_err());
+
+        // Both pieces are in the start
+        assert_eq!(game.available_moves(&state), vec![Move::Advance(0)]);
+        assert!(game.act(Player::X, Move::Advance(0), &mut state).is_ok());
+        assert_eq!(game.current_player(&state), Player::O);
+
+        // O lands on the X piece, which goes back to the start
+        assert!(game.act(Player::O, Move::Roll(3), &mut state).is_ok());
+        assert!(game.act(Player::O, Move::Advance(1), &mut state).is_ok());
+        assert_eq!(state.pieces, [[0, 0], [0, 3]]);
+
+        // The pieces past the goal are home, where they can't be sent back
+        state.pieces = [[GOAL, 10], [3, GOAL - 1]];
+        state.player = Player::X;
+        assert!(game.act(Player::X, Move::Roll(5), &mut state).is_ok());
+        assert_eq!(game.available_moves(&state), vec![Move::Advance(1)]);
+        assert!(game.act(Player::X, Move::Advance(0), &mut state).is_err());
+        assert!(game.act(Player::X, Move::Advance(1), &mut state).is_ok());
+        assert_eq!(state.pieces, [[GOAL, GOAL], [3, GOAL - 1]]);
+        assert_eq!(game.status(&state), Status::Finished(Some(Player::X)));
+        assert!(game.chance_events(&state).is_empty());
+    }
+
+    #[test]
+    fn test_chance_events() {
+        let game = DiceRace;
+        let state = game.initial_state();
+        let chance_events = game.chance_events(&state);
+
+        assert_eq!(chance_events.len(), 6);
+        assert!(
+            (chance_events
+                .iter()
+                .map(|&(_, probability)| probability)
+                .sum::<f64>()
+                - 1.0)
+                .abs()
+                < 1e-9
+        );
+
+        // The rolls are drawn until the player decides
+        let mut sampled_state = state.clone();
+        let events =
+            game.sample_chance_events(&mut sampled_state, &mut fastrand::Rng::with_seed(0));
+        assert_eq!(events.len(), 1);
+        assert_eq!(sampled_state.roll.map(Move::Roll), Some(events[0]));
+        assert!(game
+            .sample_chance_events(&mut sampled_state, &mut fastrand::Rng::with_seed(0))
+            .is_empty());
+
+        for action in [Move::Roll(4), Move::Advance(1)] {
+            assert_eq!(action.to_string().parse::<Move>(), Ok(action));
+        }
+        assert!("0".parse::<Move>().is_err());
+    }
+}
@@ -0,0 +1,357 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Game, MoveError, MoveErrorKind, Player, Status};
+
+/// Kuhn poker, the smallest poker game. The deck has three cards (jack, queen and king), each
+/// player puts one chip in the pot and is dealt one card, which the other player doesn't see.
+///
+/// Then there is a single round of betting: X checks or bets one chip, and the player facing a bet
+/// calls it or folds, otherwise it checks or bets. When nobody folds the highest card wins the pot.
+///
+/// The deal is a chance event, see `Game::chance_events`.
+pub struct KuhnPoker;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Card {
+    Jack,
+    Queen,
+    King,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct State {
+    /// The card of each player, indexed by `Player as usize`. It's `None` in the information set
+    /// of the other player.
+    cards: [Option<Card>; 2],
+    dealt: bool,
+    /// The betting moves, in the order they were played.
+    bets: Vec<Move>,
+}
+
+/// Written as `deal-<X CARD><O CARD>` for the deal (e.g., `deal-KJ`) and as its name for the bets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Move {
+    /// The cards of X and O.
+    Deal(Card, Card),
+    Check,
+    Bet,
+    Call,
+    Fold,
+}
+
+const CARDS: [Card; 3] = [Card::Jack, Card::Queen, Card::King];
+
+impl Game for KuhnPoker {
+    type State = State;
+    type Action = Move;
+
+    fn initial_state(&self) -> State {
+        State {
+            cards: [None; 2],
+            dealt: false,
+            bets: Vec::new(),
+        }
+    }
+
+    /// The card of the other player is hidden in the information sets (see
+    /// `Game::information_set`), so their showdowns finish without a known winner.
+    fn status(&self, state: &State) -> Status {
+        match state.bets[..] {
+            [.., Move::Fold] => {
+                // The player that folded made the last move, so it's the next player's turn
+                Status::Finished(Some(self.current_player(state)))
+            }
+            [Move::Check, Move::Check] | [.., Move::Call] => match state.cards {
+                [Some(x_card), Some(o_card)] if x_card > o_card => {
+                    Status::Finished(Some(Player::X))
+                }
+                [Some(_), Some(_)] => Status::Finished(Some(Player::O)),
+                _ => Status::Finished(None),
+            },
+            _ => Status::OnGoing,
+        }
+    }
+
+    fn available_moves(&self, state: &State) -> Vec<Move> {
+        if !state.dealt || self.status(state) != Status::OnGoing {
+            return Vec::new();
+        }
+
+        match state.bets.last() {
+            Some(Move::Bet) => vec![Move::Call, Move::Fold],
+            _ => vec![Move::Check, Move::Bet],
+        }
+    }
+
+    fn act(&self, _: Player, action: Move, state: &mut State) -> Result<(), MoveError<Self>> {
+        match action {
+            Move::Deal(x_card, o_card) if !state.dealt && x_card != o_card => {
+                state.cards = [Some(x_card), Some(o_card)];
+                state.dealt = true;
+            }
+            action if self.available_moves(state).contains(&action) => state.bets.push(action),
+            _ => return Err(MoveError::new(MoveErrorKind::Illegal, action, state)),
+        }
+
+        Ok(())
+    }
+
+    fn current_player(&self, state: &State) -> Player {
+        if state.bets.len().is_multiple_of(2) {
+            Player::X
+        } else {
+            Player::O
+        }
+    }
+
+    /// Each card takes 2 bits and each bet 3 bits, after the number of bets so the states with
+    /// different numbers of bets don't collide.
+    fn encode(&self, state: &State) -> u64 {
+        let card = |card: Option<Card>| card.map_or(0, |card| card as u64 + 1);
+        let bet = |bet: &Move| match bet {
+            Move::Deal(..) => 0,
+            Move::Check => 1,
+            Move::Bet => 2,
+            Move::Call => 3,
+            Move::Fold => 4,
+        };
+
+        state.bets.iter().fold(
+            ((state.bets.len() as u64) << 4 | card(state.cards[0]) << 2 | card(state.cards[1]))
+                << 1
+                | state.dealt as u64,
+            |encoded_state, bet_move| encoded_state << 3 | bet(bet_move),
+        )
+    }
+
+    fn stochastic(&self) -> bool {
+        true
+    }
+
+    fn chance_events(&self, state: &State) -> Vec<(Move, f64)> {
+        if state.dealt {
+            return Vec::new();
+        }
+
+        CARDS
+            .into_iter()
+            .flat_map(|x_card| {
+                CARDS
+                    .into_iter()
+                    .filter(move |&o_card| o_card != x_card)
+                    .map(move |o_card| (Move::Deal(x_card, o_card), 1.0 / 6.0))
+            })
+            .collect()
+    }
+
+    fn hidden_information(&self) -> bool {
+        true
+    }
+
+    fn information_set(&self, state: &State, player: Player) -> State {
+        let mut information_set = state.clone();
+        information_set.cards[player.next_player() as usize] = None;
+
+        information_set
+    }
+
+    /// The chips of the loser in the pot, one more when the bet was called.
+    fn utility(&self, state: &State, player: Player) -> f64 {
+        let Status::Finished(Some(winner)) = self.status(state) else {
+            return 0.0;
+        };
+
+        let chips = if state.bets.contains(&Move::Call) {
+            2.0
+        } else {
+            1.0
+        };
+
+        if winner == player {
+            chips
+        } else {
+            -chips
+        }
+    }
+
+    fn name(&self) -> String {
+        "kuhn-poker".to_string()
+    }
+}
+
+impl Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.dealt {
+            return writeln!(f, "The cards are not dealt");
+        }
+
+        let [x_card, o_card] = self
+            .cards
+            .map(|card| card.map_or("?".to_string(), |card| card.to_string()));
+        writeln!(f, "X has {x_card}, O has {o_card}")?;
+
+        let bets = self
+            .bets
+            .iter()
+            .map(Move::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        writeln!(f, "Bets: {bets}")
+    }
+}
+
+impl Display for Card {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let card = match self {
+            Card::Jack => "J",
+            Card::Queen => "Q",
+            Card::King => "K",
+        };
+
+        write!(f, "{card}")
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Move::Deal(x_card, o_card) => write!(f, "deal-{x_card}{o_card}"),
+            Move::Check => write!(f, "check"),
+            Move::Bet => write!(f, "bet"),
+            Move::Call => write!(f, "call"),
+            Move::Fold => write!(f, "fold"),
+        }
+    }
+}
+
+impl FromStr for Move {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let card = |card| match card {
+            'J' => Some(Card::Jack),
+            'Q' => Some(Card::Queen),
+            'K' => Some(Card::King),
+            _ => None,
+        };
+
+        match s {
+            "check" => Ok(Move::Check),
+            "bet" => Ok(Move::Bet),
+            "call" => Ok(Move::Call),
+            "fold" => Ok(Move::Fold),
+            _ => s
+                .strip_prefix("deal-")
+                .and_then(|cards| {
+                    let mut cards = cards.chars().map(card);
+
+                    match (cards.next(), cards.next(), cards.next()) {
+                        (Some(Some(x_card)), Some(Some(o_card)), None) => {
+                            Some(Move::Deal(x_card, o_card))
+                        }
+                        _ => None,
+                    }
+                })
+                .ok_or_else(|| {
+                    format!("Invalid move '{s}', expected 'check', 'bet', 'call' or 'fold'")
+                }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn play(moves: &[&str]) -> State {
+        let mut state = KuhnPoker.initial_state();
+
+        for action in moves {
+            let player = KuhnPoker.current_player(&state);
+            KuhnPoker
+                .act(player, action.parse().unwrap(), &mut state)
+                .unwrap();
+        }
+
+        state
+    }
+
+    #[test]
+    fn test_act() {
+        let state = play(&[]);
+        assert_eq!(KuhnPoker.chance_events(&state).len(), 6);
+        assert!(KuhnPoker.available_moves(&state).is_empty());
+
+        let mut state = play(&["deal-QK"]);
+        assert!(KuhnPoker.chance_events(&state).is_empty());
+        assert_eq!(
+            KuhnPoker.available_moves(&state),
+            vec![Move::Check, Move::Bet]
+        );
+        assert!(KuhnPoker.act(Player::X, Move::Call, &mut state).is_err());
+        assert!("deal-QQ"
+            .parse::<Move>()
+            .is_ok_and(|action| KuhnPoker.act(Player::X, action, &mut state).is_err()));
+
+        // The player with the highest card wins the showdown
+        for (moves, winner, chips) in [
+            (vec!["deal-QK", "check", "check"], Player::O, 1.0),
+            (vec!["deal-QK", "bet", "fold"], Player::X, 1.0),
+            (vec!["deal-QK", "check", "bet", "fold"], Player::O, 1.0),
+            (vec!["deal-QK", "check", "bet", "call"], Player::O, 2.0),
+            (vec!["deal-KJ", "bet", "call"], Player::X, 2.0),
+        ] {
+            let state = play(&moves);
+
+            assert_eq!(KuhnPoker.status(&state), Status::Finished(Some(winner)));
+            assert!(KuhnPoker.available_moves(&state).is_empty());
+            assert_eq!(KuhnPoker.utility(&state, winner), chips);
+            assert_eq!(KuhnPoker.utility(&state, winner.next_player()), -chips);
+        }
+    }
+
+    #[test]
+    fn test_information_set() {
+        let state = play(&["deal-QK", "check"]);
+        let information_set = KuhnPoker.information_set(&state, Player::O);
+
+        assert_eq!(
+            information_set.to_string(),
+            "X has ?, O has K\nBets: check\n"
+        );
+        assert_eq!(
+            KuhnPoker.available_moves(&information_set),
+            KuhnPoker.available_moves(&state)
+        );
+
+        // Each player only tells the deals apart by its own card
+        let encode = |moves: &[&str], player| {
+            KuhnPoker.encode(&KuhnPoker.information_set(&play(moves), player))
+        };
+        assert_eq!(
+            KuhnPoker.encode(&information_set),
+            encode(&["deal-JK", "check"], Player::O)
+        );
+        assert_eq!(
+            encode(&["deal-QK", "check"], Player::X),
+            encode(&["deal-QJ", "check"], Player::X)
+        );
+        assert_ne!(
+            encode(&["deal-QK", "check"], Player::X),
+            encode(&["deal-KQ", "check"], Player::X)
+        );
+
+        // The showdown can't be decided without the other card
+        let state = play(&["deal-QK", "check", "check"]);
+        for player in [Player::X, Player::O] {
+            let information_set = KuhnPoker.information_set(&state, player);
+            assert_eq!(KuhnPoker.status(&information_set), Status::Finished(None));
+        }
+
+        for action in [Move::Deal(Card::King, Card::Jack), Move::Check, Move::Fold] {
+            assert_eq!(action.to_string().parse::<Move>(), Ok(action));
+        }
+    }
+}
@@ -0,0 +1,439 @@
+use std::{fmt::Display, str::FromStr};
+
+use super::{
+    field_label, labelled_board, parse_field_label, parse_fields, splitmix64, Game, MoveError,
+    MoveErrorKind, Player, Status,
+};
+
+/// The biggest board that can be encoded exactly in an `u64`, as `3 ** 40 < 2 ** 64`.
+const MAX_EXACT_FIELDS: usize = 40;
+
+/// The m,n,k-game, a generalization of Tic-Tac-Toe played on a `width` x `height` board where the
+/// first player to line up `k` pieces (horizontally, vertically or diagonally) wins.
+///
+/// Some known members of the family are Tic-Tac-Toe (3x3, k = 3) and Gomoku (15x15, k = 5).
+pub struct MnkGame {
+    width: u16,
+    height: u16,
+    k: u16,
+}
+
+#[derive(Clone, Debug, Eq)]
+pub struct State {
+    fields: Vec<Option<Player>>,
+    available_fields: Vec<u16>,
+    winner: Option<Player>,
+    encoded_state: u64,
+    // Only used to display the board, the game holds the rules
+    width: u16,
+}
+
+/// The board dimensions, parsed from the `<WIDTH>x<HEIGHT>` format (e.g. `15x15`).
+#[derive(Clone, Copy, Debug)]
+pub struct Board {
+    pub width: u16,
+    pub height: u16,
+}
+
+impl Game for MnkGame {
+    type State = State;
+    type Action = u16;
+
+    fn initial_state(&self) -> State {
+        let size = self.width * self.height;
+
+        State {
+            fields: vec![None; size as usize],
+            available_fields: (0..size).collect(),
+            winner: None,
+            encoded_state: 0,
+            width: self.width,
+        }
+    }
+
+    fn status(&self, state: &State) -> Status {
+        if state.winner.is_some() {
+            Status::Finished(state.winner)
+        } else if state.available_fields.is_empty() {
+            Status::Finished(None)
+        } else {
+            Status::OnGoing
+        }
+    }
+
+    fn available_moves(&self, state: &State) -> Vec<u16> {
+        state.available_fields.clone()
+    }
+
+    fn act(&self, player: Player, position: u16, state: &mut State) -> Result<(), MoveError<Self>> {
+        if position as usize >= state.fields.len() {
+            return Err(MoveError::new(MoveErrorKind::OutOfBound, position, state));
+        };
+
+        if state.fields[position as usize].is_some() {
+            return Err(MoveError::new(
+                MoveErrorKind::NonEmptyField,
+                position,
+                state,
+            ));
+        }
+
+        let field = &mut state.fields[position as usize];
+
+        if let Some(index) = state
+            .available_fields
+            .iter()
+            .position(|&value| value == position)
+        {
+            state.available_fields.swap_remove(index);
+        }
+
+        field.replace(player);
+        state.encoded_state = self.encode_field(state.encoded_state, player, position);
+
+        if self.is_winning_move(state, position) {
+            state.winner = Some(player);
+        }
+
+        Ok(())
+    }
+
+    fn current_player(&self, state: &State) -> Player {
+        if (state.fields.len() - state.available_fields.len()).is_multiple_of(2) {
+            Player::X
+        } else {
+            Player::O
+        }
+    }
+
+    fn parse_state(&self, board: &str) -> Result<State, String> {
+        let fields = parse_fields(board, self.width as usize, self.height as usize)?;
+
+        Ok(self.state_from_fields(&fields))
+    }
+
+    /// For small boards the state is encoded exactly (like in Tic-Tac-Toe), but for larger boards
+    /// there are way too many states to fit in an `u64`, so we use a Zobrist hash instead and
+    /// accept the (tiny) chance of collisions.
+    fn encode(&self, state: &State) -> u64 {
+        state.encoded_state
+    }
+
+    fn label(&self, position: u16) -> String {
+        field_label(position as usize, self.width as usize)
+    }
+
+    fn parse_label(&self, label: &str) -> Option<u16> {
+        parse_field_label(label, self.width as usize, self.height as usize)
+            .map(|position| position as u16)
+    }
+
+    fn labelled_board(&self, state: &State) -> String {
+        if self.width > 26 {
+            return state.to_string();
+        }
+
+        labelled_board(self.width as usize, self.height as usize, |position| {
+            state.fields[position]
+        })
+    }
+
+    fn name(&self) -> String {
+        format!("mnk-{}x{}-{}", self.width, self.height, self.k)
+    }
+}
+
+impl MnkGame {
+    /// The `k` pieces must fit in a row or a column of the board.
+    pub(crate) fn new(board: Board, k: u16) -> Result<Self, String> {
+        if k == 0 || k > board.width.max(board.height) {
+            return Err(format!(
+                "Invalid k '{k}', it should be between 1 and the longest side of the {board} board"
+            ));
+        }
+
+        Ok(MnkGame {
+            width: board.width,
+            height: board.height,
+            k,
+        })
+    }
+
+    fn state_from_fields(&self, fields: &[Option<Player>]) -> State {
+        let mut state = self.initial_state();
+
+        for (position, field) in fields.iter().enumerate() {
+            if let Some(player) = field {
+                // SAFETY: The positions are drawn from the (empty) board itself
+                unsafe {
+                    self.act(*player, position as u16, &mut state)
+                        .unwrap_unchecked()
+                };
+            }
+        }
+
+        state
+    }
+
+    fn encode_field(&self, encoded_state: u64, player: Player, position: u16) -> u64 {
+        let value = match player {
+            Player::X => 1,
+            Player::O => 2,
+        };
+
+        if (self.width * self.height) as usize <= MAX_EXACT_FIELDS {
+            encoded_state + value * u64::pow(3, position as u32)
+        } else {
+            encoded_state ^ splitmix64(position as u64 * 2 + value)
+        }
+    }
+
+    /// Checks if the piece at `position` is part of a line with at least `k` pieces.
+    fn is_winning_move(&self, state: &State, position: u16) -> bool {
+        let player = state.fields[position as usize];
+        let column = (position % self.width) as i32;
+        let row = (position / self.width) as i32;
+
+        // horizontal, vertical, diagonal (\) and diagonal (/)
+        for (dx, dy) in [(1, 0), (0, 1), (1, 1), (1, -1)] {
+            let mut count = 1;
+
+            for direction in [1, -1] {
+                let (mut x, mut y) = (column, row);
+
+                loop {
+                    x += dx * direction;
+                    y += dy * direction;
+
+                    if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+                        break;
+                    }
+
+                    if state.fields[(y * self.width as i32 + x) as usize] != player {
+                        break;
+                    }
+
+                    count += 1;
+                }
+            }
+
+            if count >= self.k {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+impl PartialEq for State {
+    fn eq(&self, other: &Self) -> bool {
+        self.fields == other.fields
+    }
+}
+
+impl Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = self.width as usize;
+        let separator = vec!["---"; width].join("+");
+
+        for (i, field) in self.fields.iter().enumerate() {
+            match field {
+                Some(player) => write!(f, " {player} ")?,
+                None => write!(f, "   ")?,
+            };
+
+            if i % width < width - 1 {
+                write!(f, "|")?;
+            } else {
+                writeln!(f)?;
+
+                if i == self.fields.len() - 1 {
+                    break;
+                }
+
+                writeln!(f, "{separator}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Board {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("Invalid board '{s}', expected the '<WIDTH>x<HEIGHT>' format");
+
+        let (width, height) = s.split_once('x').ok_or_else(error)?;
+        let width: u16 = width.parse().map_err(|_| error())?;
+        let height: u16 = height.parse().map_err(|_| error())?;
+
+        if width == 0 || height == 0 || width.checked_mul(height).is_none() {
+            return Err(error());
+        }
+
+        Ok(Board { width, height })
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const GAME: MnkGame = MnkGame {
+        width: 4,
+        height: 4,
+        k: 3,
+    };
+
+    macro_rules! state {
+        ($($s:tt)+) => {
+            GAME.state_from_fields(&fields![$($s)+])
+        };
+    }
+
+    #[test]
+    fn test_act() {
+        let mut state = state![
+            X O - -
+            - - - -
+            - - - -
+            - - - -
+        ];
+
+        assert!(GAME.act(Player::X, 5, &mut state).is_ok());
+        assert_eq!(
+            state,
+            state![
+                X O - -
+                - X - -
+                - - - -
+                - - - -
+            ]
+        );
+        assert_eq!(GAME.status(&state), Status::OnGoing);
+
+        assert!(GAME.act(Player::O, 0, &mut state).is_err());
+        assert!(GAME.act(Player::O, 16, &mut state).is_err());
+
+        assert!(GAME.act(Player::O, 2, &mut state).is_ok());
+        assert!(GAME.act(Player::X, 10, &mut state).is_ok());
+        assert_eq!(
+            state,
+            state![
+                X O O -
+                - X - -
+                - - X -
+                - - - -
+            ]
+        );
+        assert_eq!(GAME.available_moves(&state).len(), 11);
+        assert_eq!(GAME.status(&state), Status::Finished(Some(Player::X)));
+    }
+
+    #[test]
+    fn test_status() {
+        assert_eq!(
+            GAME.status(&state![
+                - - - -
+                - X X X
+                O O - -
+                - - - -
+            ]),
+            Status::Finished(Some(Player::X))
+        );
+        assert_eq!(
+            GAME.status(&state![
+                - - - X
+                X X - -
+                O O - -
+                - - - -
+            ]),
+            Status::OnGoing
+        );
+        assert_eq!(
+            GAME.status(&state![
+                - X - -
+                - - O X
+                - - O X
+                - - O -
+            ]),
+            Status::Finished(Some(Player::O))
+        );
+        assert_eq!(
+            GAME.status(&state![
+                - - - -
+                - - - X
+                O O X -
+                - X - O
+            ]),
+            Status::Finished(Some(Player::X))
+        );
+        assert_eq!(
+            GAME.status(&state![
+                X X O O
+                O O X X
+                X X O O
+                O O X X
+            ]),
+            Status::Finished(None)
+        );
+    }
+
+    #[test]
+    fn test_encode() {
+        let small = MnkGame::new(
+            Board {
+                width: 3,
+                height: 3,
+            },
+            3,
+        )
+        .unwrap();
+        let large = MnkGame::new(
+            Board {
+                width: 15,
+                height: 15,
+            },
+            5,
+        )
+        .unwrap();
+
+        for game in [small, large] {
+            let mut state = game.initial_state();
+            let mut other = game.initial_state();
+
+            assert!(game.act(Player::X, 0, &mut state).is_ok());
+            assert!(game.act(Player::O, 1, &mut state).is_ok());
+            assert!(game.act(Player::O, 1, &mut other).is_ok());
+            assert!(game.act(Player::X, 0, &mut other).is_ok());
+            assert_eq!(game.encode(&state), game.encode(&other));
+
+            assert!(game.act(Player::X, 2, &mut other).is_ok());
+            assert_ne!(game.encode(&state), game.encode(&other));
+        }
+    }
+
+    #[test]
+    fn test_board() {
+        let board: Board = "15x10".parse().unwrap();
+
+        assert_eq!((board.width, board.height), (15, 10));
+        assert!("15".parse::<Board>().is_err());
+        assert!("0x3".parse::<Board>().is_err());
+        assert!("ax3".parse::<Board>().is_err());
+
+        // The line must fit in the board, the longest side of a 15x10 board is 15
+        assert!(MnkGame::new(board, 15).is_ok());
+        assert!(MnkGame::new(board, 16).is_err());
+        assert!(MnkGame::new(board, 0).is_err());
+    }
+}
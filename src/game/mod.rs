@@ -0,0 +1,760 @@
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::str::FromStr;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::players::Decision;
+
+/// Helper macro to make the board easier to see for humans, it enable us to define a board state
+/// like this:
+///
+/// # Example
+///
+/// ```
+///     fields![
+///         X O X
+///         - X -
+///         O O X
+///     ]
+/// ```
+#[cfg(test)]
+macro_rules! fields {
+    (O) => {Some(Player::O)};
+    (X) => {Some(Player::X)};
+    (-) => {None};
+    (_) => {_};
+    ($($s:tt)+) => {
+        [$(fields!($s)),+]
+    };
+}
+
+mod connect_four;
+mod dice_race;
+mod kuhn_poker;
+mod mnk;
+mod nim;
+mod othello;
+mod tictactoe;
+mod ultimate;
+mod wild;
+
+pub(crate) use connect_four::ConnectFour;
+pub(crate) use dice_race::DiceRace;
+pub(crate) use kuhn_poker::KuhnPoker;
+pub(crate) use mnk::{Board, MnkGame};
+pub(crate) use nim::Nim;
+pub(crate) use othello::Othello;
+pub(crate) use tictactoe::TicTacToe;
+pub(crate) use ultimate::UltimateTicTacToe;
+pub(crate) use wild::WildTicTacToe;
+
+/// The interface between the games and the players.
+///
+/// Every game is a two-player, turn-taking game where `Player::X` always starts. The game itself
+/// holds only its rules (and configuration), everything that changes during a match lives in the
+/// `State`.
+///
+/// The players always alternate, a player that can't move in a non-terminal state must still have
+/// an action that passes the turn (e.g., `othello::Move::Pass`).
+///
+/// The stochastic games have chance events (e.g., a roll of the dice) before the players move, see
+/// `Game::chance_events`. They are played by `Game::act` like the moves, but they don't count as a
+/// turn, so the players still alternate.
+///
+/// In the games with hidden information (e.g., the cards of the other players) the players only see
+/// their information set, see `Game::information_set`.
+pub trait Game: Sync + Send + Sized + 'static {
+    type State: Clone + Display + Send + Sync;
+    type Action: Copy
+        + Eq
+        + Hash
+        + Debug
+        + Display
+        + FromStr
+        + Serialize
+        + DeserializeOwned
+        + Send
+        + Sync;
+
+    fn initial_state(&self) -> Self::State;
+
+    fn status(&self, state: &Self::State) -> Status;
+
+    fn available_moves(&self, state: &Self::State) -> Vec<Self::Action>;
+
+    /// Act in the state, mutating it. The state is left untouched when the move is illegal.
+    fn act(
+        &self,
+        player: Player,
+        action: Self::Action,
+        state: &mut Self::State,
+    ) -> Result<(), MoveError<Self>>;
+
+    /// Whether the game can take back its moves in place, see `Game::undo`.
+    fn can_undo(&self) -> bool {
+        false
+    }
+
+    /// Takes back the `action`, which must be the last one the `player` played in the state. The
+    /// searches make and unmake the moves in a single state with it, instead of playing each move
+    /// in a copy of the state. Only the games where `Game::can_undo` is `true` implement it.
+    fn undo(&self, _player: Player, _action: Self::Action, _state: &mut Self::State) {
+        unimplemented!("The {} game can't undo its moves", self.name())
+    }
+
+    /// The player that moves next in the state.
+    fn current_player(&self, state: &Self::State) -> Player;
+
+    /// Parses a board with the rows separated by `|` and the fields written as `X`, `O` or `-`
+    /// (empty), e.g. `XO-|-X-|--O`. Not all the games can be described by their board alone.
+    fn parse_state(&self, _board: &str) -> Result<Self::State, String> {
+        Err(format!(
+            "The {} game does not support parsing boards",
+            self.name()
+        ))
+    }
+
+    /// Transforms the state in an unique integer, so tabular agents can use it as a key.
+    fn encode(&self, state: &Self::State) -> u64;
+
+    /// The encoding of the canonical form of the state, which is shared by all the states that are
+    /// symmetric to it (e.g., the rotations of the board), and the symmetry that turns the state
+    /// into its canonical form. The games without symmetries use the encoding of the state.
+    fn canonical_encode(&self, state: &Self::State) -> (u64, Symmetry) {
+        (self.encode(state), Symmetry::IDENTITY)
+    }
+
+    /// The action in the board transformed by the `symmetry`, see `Game::canonical_encode`.
+    fn transform_action(&self, action: Self::Action, _symmetry: Symmetry) -> Self::Action {
+        action
+    }
+
+    /// The Sprague-Grundy number of the state, which is `0` when the state is lost for the player to
+    /// move. Only the impartial games, where both players have the same moves, have them.
+    fn grundy_number(&self, _state: &Self::State) -> Option<u32> {
+        None
+    }
+
+    /// Whether the game has chance events, the agents that assume every action is chosen by a
+    /// player can't play it.
+    fn stochastic(&self) -> bool {
+        false
+    }
+
+    /// The chance events that happen in the state before the player to move decides, with their
+    /// probabilities adding up to `1`. It's empty when a player decides, so always in the
+    /// deterministic games. The players have no available moves while it isn't.
+    fn chance_events(&self, _state: &Self::State) -> Vec<(Self::Action, f64)> {
+        Vec::new()
+    }
+
+    /// Plays random chance events, drawn by their probabilities, until a player has to decide.
+    /// Returns the events played.
+    fn sample_chance_events(
+        &self,
+        state: &mut Self::State,
+        rng: &mut fastrand::Rng,
+    ) -> Vec<Self::Action> {
+        let mut events = Vec::new();
+
+        loop {
+            let chance_events = self.chance_events(state);
+
+            let Some(&(mut event, _)) = chance_events.last() else {
+                return events;
+            };
+
+            // The last event takes whatever is left, in case the probabilities don't add up to 1
+            let mut draw = rng.f64();
+            for &(chance_event, probability) in &chance_events {
+                if draw < probability {
+                    event = chance_event;
+                    break;
+                }
+
+                draw -= probability;
+            }
+
+            let player = self.current_player(state);
+
+            // SAFETY: we draw the events from the `chance_events` method
+            unsafe { self.act(player, event, state).unwrap_unchecked() };
+            events.push(event);
+        }
+    }
+
+    /// Whether the players see only part of the state, see `Game::information_set`.
+    fn hidden_information(&self) -> bool {
+        false
+    }
+
+    /// What the `player` knows about the state (its information set), the state with everything
+    /// hidden from the player removed. The players decide with it, but the rules need the full
+    /// state, so only the available moves of the information set are known.
+    fn information_set(&self, state: &Self::State, _player: Player) -> Self::State {
+        state.clone()
+    }
+
+    /// The payoff of the player in a terminal state: `1` for a victory, `-1` for a loss and `0` for
+    /// a draw. It's overridden by the games where the amount won varies (e.g., the chips in poker).
+    fn utility(&self, state: &Self::State, player: Player) -> f64 {
+        match self.status(state) {
+            Status::Finished(Some(winner)) if winner == player => 1.0,
+            Status::Finished(Some(_)) => -1.0,
+            Status::Finished(None) | Status::OnGoing => 0.0,
+        }
+    }
+
+    /// How the action is shown to humans, e.g. the coordinates of the field.
+    fn label(&self, action: Self::Action) -> String {
+        action.to_string()
+    }
+
+    /// Parses the label of an action (see `Game::label`), the actions themselves are accepted too.
+    fn parse_label(&self, label: &str) -> Option<Self::Action> {
+        label.parse().ok()
+    }
+
+    /// The state drawn for humans, with the labels of the actions when they are not obvious.
+    fn labelled_board(&self, state: &Self::State) -> String {
+        state.to_string()
+    }
+
+    /// The name used to identify the game, e.g. in the learned agents files.
+    fn name(&self) -> String;
+
+    /// Estimates how good a non-terminal state is for the player. It's used by the agents that
+    /// can't search until the end of the game, so it must be way smaller than a victory (we use
+    /// values below `1000`).
+    fn heuristic(&self, _state: &Self::State, _player: Player) -> i64 {
+        0
+    }
+
+    /// Plays a game, the illegal moves are handled as the `policy` says. The players are told how
+    /// the game ended, see `Player::game_over`.
+    fn play(
+        &self,
+        player_1: &dyn crate::Player<Self>,
+        player_2: &dyn crate::Player<Self>,
+        policy: IllegalMovePolicy,
+        rng: &mut fastrand::Rng,
+    ) -> GameRecord<Self> {
+        let mut board = self.initial_state();
+        let mut moves = Vec::new();
+        let mut decisions = Vec::new();
+        let mut illegal_moves = Vec::new();
+        // The illegal moves in a row of the current player
+        let mut retries = 0;
+
+        let (winner, forfeit) = loop {
+            // Some games are over before the first move (e.g., Nim without pieces to take)
+            if let Status::Finished(winner) = self.status(&board) {
+                break (winner, None);
+            }
+
+            moves.extend(self.sample_chance_events(&mut board, rng));
+
+            let player = self.current_player(&board);
+            let information_set = self.information_set(&board, player);
+
+            let decision = match player {
+                Player::X => player_1.decide(self, &information_set, player, rng),
+                Player::O => player_2.decide(self, &information_set, player, rng),
+            };
+
+            let mut action = match decision {
+                Decision::Play(action) => action,
+                Decision::Undo => {
+                    if decisions.len() < 2 {
+                        match player {
+                            Player::X => player_1.cannot_undo(self, player),
+                            Player::O => player_2.cannot_undo(self, player),
+                        }
+
+                        continue;
+                    }
+
+                    // Takes back the last move of each player, so it's the same player's turn again.
+                    // The chance events before the move are kept, so they are not drawn again.
+                    moves.truncate(decisions[decisions.len() - 2]);
+                    decisions.truncate(decisions.len() - 2);
+                    board = self.initial_state();
+
+                    for &action in &moves {
+                        let player = self.current_player(&board);
+
+                        // SAFETY: The moves were already played in this order
+                        unsafe { self.act(player, action, &mut board).unwrap_unchecked() };
+                    }
+
+                    continue;
+                }
+                Decision::Resign => break (Some(player.next_player()), Some(player)),
+            };
+
+            if let Err(error) = self.act(player, action, &mut board) {
+                illegal_moves.push((player, error));
+
+                match policy {
+                    IllegalMovePolicy::Retry(max_retries) if retries < max_retries => {
+                        // The same player tries again
+                        retries += 1;
+                        continue;
+                    }
+                    IllegalMovePolicy::Substitute => {
+                        let available_moves = self.available_moves(&board);
+
+                        // There is no legal move to play instead, so the player forfeits
+                        if available_moves.is_empty() {
+                            break (Some(player.next_player()), Some(player));
+                        }
+
+                        action = available_moves[rng.usize(..available_moves.len())];
+
+                        // SAFETY: we draw the action from the `available_moves` method
+                        unsafe { self.act(player, action, &mut board).unwrap_unchecked() };
+                    }
+                    IllegalMovePolicy::Forfeit | IllegalMovePolicy::Retry(_) => {
+                        break (Some(player.next_player()), Some(player));
+                    }
+                }
+            };
+
+            retries = 0;
+            decisions.push(moves.len());
+            moves.push(action);
+        };
+
+        player_1.game_over(self, &board, Player::X, winner);
+        player_2.game_over(self, &board, Player::O, winner);
+
+        GameRecord {
+            winner,
+            moves,
+            decisions,
+            illegal_moves,
+            forfeit,
+        }
+    }
+}
+
+/// The summary of a finished game.
+pub struct GameRecord<G: Game> {
+    pub winner: Option<Player>,
+    /// The moves in the order they were played, including the ones played instead of the illegal
+    /// moves and the chance events.
+    pub moves: Vec<G::Action>,
+    /// Where each move of the players is in the `moves`, the others are chance events.
+    pub decisions: Vec<usize>,
+    /// The illegal moves that each player tried, in the order they were tried.
+    pub illegal_moves: Vec<(Player, MoveError<G>)>,
+    /// The player that gave the game up, by resigning or by trying too many illegal moves.
+    pub forfeit: Option<Player>,
+}
+
+/// What happens when a player tries an illegal move in `Game::play`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IllegalMovePolicy {
+    /// The player loses the game.
+    Forfeit,
+    /// The player tries again, up to the given number of times in a row, then it forfeits.
+    Retry(u32),
+    /// A random legal move is played instead, the player forfeits when there is none.
+    Substitute,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Status {
+    Finished(Option<Player>),
+    OnGoing,
+}
+
+/// A move that could not be played, with the state where it was tried.
+pub struct MoveError<G: Game> {
+    pub kind: MoveErrorKind,
+    pub action: G::Action,
+    pub state: G::State,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoveErrorKind {
+    NonEmptyField,
+    OutOfBound,
+    /// The move is not allowed by the game rules in the current state.
+    Illegal,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Player {
+    X,
+    O,
+}
+
+/// One of the 8 symmetries of a square board: a reflection across the vertical axis (when
+/// `reflected`) followed by `rotations` clockwise quarter turns.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Symmetry {
+    reflected: bool,
+    rotations: u8,
+}
+
+impl Symmetry {
+    pub const IDENTITY: Symmetry = Symmetry {
+        reflected: false,
+        rotations: 0,
+    };
+
+    /// All the symmetries, ordered by `Symmetry::index`.
+    const ALL: [Symmetry; 8] = [
+        Symmetry::IDENTITY,
+        Symmetry::new(false, 1),
+        Symmetry::new(false, 2),
+        Symmetry::new(false, 3),
+        Symmetry::new(true, 0),
+        Symmetry::new(true, 1),
+        Symmetry::new(true, 2),
+        Symmetry::new(true, 3),
+    ];
+
+    const fn new(reflected: bool, rotations: u8) -> Self {
+        Symmetry {
+            reflected,
+            rotations,
+        }
+    }
+
+    const fn index(self) -> usize {
+        self.reflected as usize * 4 + self.rotations as usize
+    }
+
+    pub fn inverse(self) -> Symmetry {
+        // A reflection followed by a rotation is its own inverse
+        if self.reflected {
+            self
+        } else {
+            Symmetry::new(false, (4 - self.rotations) % 4)
+        }
+    }
+
+    /// Where the field in the `column` and `row` of a `size` x `size` board goes, the rows go from
+    /// top to bottom.
+    const fn apply(self, column: usize, row: usize, size: usize) -> (usize, usize) {
+        let (mut column, mut row) = (column, row);
+
+        if self.reflected {
+            column = size - 1 - column;
+        }
+
+        let mut rotations = 0;
+        while rotations < self.rotations {
+            (column, row) = (size - 1 - row, column);
+            rotations += 1;
+        }
+
+        (column, row)
+    }
+}
+
+/// Parses the fields of a `width` x `height` board in the format described in `Game::parse_state`,
+/// from the top row to the bottom one. As `Player::X` always starts, it must have the same number
+/// of pieces as `Player::O` or one more.
+fn parse_fields(board: &str, width: usize, height: usize) -> Result<Vec<Option<Player>>, String> {
+    let fields = parse_pieces(board, width, height)?;
+
+    let count = |player| {
+        fields
+            .iter()
+            .filter(|&&field| field == Some(player))
+            .count()
+    };
+    let (x_count, o_count) = (count(Player::X), count(Player::O));
+
+    if x_count != o_count && x_count != o_count + 1 {
+        return Err(format!(
+            "Invalid number of pieces, X has {x_count} and O has {o_count}"
+        ));
+    }
+
+    Ok(fields)
+}
+
+/// The same as `parse_fields`, for the games where the pieces don't belong to the players, so any
+/// number of each is allowed.
+fn parse_pieces(board: &str, width: usize, height: usize) -> Result<Vec<Option<Player>>, String> {
+    let rows: Vec<_> = board.trim().split('|').collect();
+
+    if rows.len() != height || rows.iter().any(|row| row.chars().count() != width) {
+        return Err(format!(
+            "The board '{board}' should have {height} rows with {width} fields each"
+        ));
+    }
+
+    rows.iter()
+        .flat_map(|row| row.chars())
+        .map(|field| match field {
+            'X' | 'x' => Ok(Some(Player::X)),
+            'O' | 'o' => Ok(Some(Player::O)),
+            '-' => Ok(None),
+            _ => Err(format!("Invalid field '{field}', expected 'X', 'O' or '-'")),
+        })
+        .collect()
+}
+
+/// The label of the field in the `position` of a board `width` fields wide, the columns are
+/// lettered from the left and the rows numbered from the top (e.g., `b3`). The boards wider than
+/// the alphabet use the positions themselves.
+fn field_label(position: usize, width: usize) -> String {
+    if width > 26 {
+        return position.to_string();
+    }
+
+    let column = (b'a' + (position % width) as u8) as char;
+
+    format!("{column}{}", position / width + 1)
+}
+
+/// Parses the label of a field (see `field_label`) or the position itself.
+fn parse_field_label(label: &str, width: usize, height: usize) -> Option<usize> {
+    let label = label.to_ascii_lowercase();
+
+    let position = match label.as_bytes() {
+        [column @ b'a'..=b'z', ..] if width <= 26 => {
+            let column = (column - b'a') as usize;
+            let row: usize = label[1..].parse().ok()?;
+
+            if column >= width || row == 0 || row > height {
+                return None;
+            }
+
+            (row - 1) * width + column
+        }
+        _ => label.parse().ok()?,
+    };
+
+    (position < width * height).then_some(position)
+}
+
+/// Draws the `width` x `height` board with the field of each position, lettering the columns and
+/// numbering the rows as in `field_label`.
+fn labelled_board(width: usize, height: usize, field: impl Fn(usize) -> Option<Player>) -> String {
+    let mut board = "   ".to_string();
+
+    for column in 0..width {
+        board += &format!(" {}  ", (b'a' + column as u8) as char);
+    }
+
+    board = board.trim_end().to_string();
+    board.push('\n');
+
+    for row in 0..height {
+        if row > 0 {
+            board += &format!("   {}\n", vec!["---"; width].join("+"));
+        }
+
+        let fields: Vec<_> = (0..width)
+            .map(|column| match field(row * width + column) {
+                Some(player) => format!(" {player} "),
+                None => "   ".to_string(),
+            })
+            .collect();
+
+        board += &format!("{:>2} {}\n", row + 1, fields.join("|"));
+    }
+
+    board
+}
+
+/// A simple and well distributed hash function, used to generate the Zobrist keys and to derive
+/// independent seeds from the seed of a run. It's deterministic so learned agents can be reloaded.
+pub(crate) fn splitmix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+impl<G: Game> MoveError<G> {
+    pub(crate) fn new(kind: MoveErrorKind, action: G::Action, state: &G::State) -> Self {
+        MoveError {
+            kind,
+            action,
+            state: state.clone(),
+        }
+    }
+}
+
+impl<G: Game> Display for MoveError<G> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Move {}: {}, in the state:", self.action, self.kind)?;
+        write!(f, "{}", self.state)
+    }
+}
+
+// The states don't need to implement `Debug`, so they are shown as they are displayed
+impl<G: Game> Debug for MoveError<G> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MoveError")
+            .field("kind", &self.kind)
+            .field("action", &self.action)
+            .field("state", &format_args!("\n{}", self.state))
+            .finish()
+    }
+}
+
+impl Display for MoveErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveErrorKind::NonEmptyField => write!(f, "the field is not empty"),
+            MoveErrorKind::OutOfBound => write!(f, "the move is out of the board"),
+            MoveErrorKind::Illegal => write!(f, "the move is not allowed by the rules"),
+        }
+    }
+}
+
+impl FromStr for IllegalMovePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "forfeit" => Ok(IllegalMovePolicy::Forfeit),
+            None if s == "substitute" => Ok(IllegalMovePolicy::Substitute),
+            Some(("retry", retries)) => retries
+                .parse()
+                .map(IllegalMovePolicy::Retry)
+                .map_err(|_| format!("Invalid number of retries '{retries}'")),
+            _ => Err(format!(
+                "Invalid policy '{s}', expected 'forfeit', 'retry:<N>' or 'substitute'"
+            )),
+        }
+    }
+}
+
+impl Display for IllegalMovePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IllegalMovePolicy::Forfeit => write!(f, "forfeit"),
+            IllegalMovePolicy::Retry(retries) => write!(f, "retry:{retries}"),
+            IllegalMovePolicy::Substitute => write!(f, "substitute"),
+        }
+    }
+}
+
+impl Player {
+    pub(crate) fn next_player(self) -> Player {
+        match self {
+            Player::X => Player::O,
+            Player::O => Player::X,
+        }
+    }
+}
+
+impl Display for Player {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            Player::X => write!(f, "X"),
+            Player::O => write!(f, "O"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    use fastrand::Rng;
+
+    use super::*;
+    use crate::ReLearnError;
+
+    /// Makes the given decisions in order and remembers how the game went.
+    struct ScriptedPlayer {
+        decisions: Mutex<Vec<Decision<u8>>>,
+        rejected_undos: Mutex<u32>,
+        final_state: Mutex<Option<tictactoe::State>>,
+    }
+
+    impl ScriptedPlayer {
+        fn new(mut decisions: Vec<Decision<u8>>) -> Self {
+            decisions.reverse();
+
+            ScriptedPlayer {
+                decisions: Mutex::new(decisions),
+                rejected_undos: Mutex::new(0),
+                final_state: Mutex::new(None),
+            }
+        }
+    }
+
+    impl crate::Player<TicTacToe> for ScriptedPlayer {
+        fn play(&self, _: &TicTacToe, _: &tictactoe::State, _: Player, _: &mut Rng) -> u8 {
+            unreachable!("The scripted player only decides")
+        }
+
+        fn decide(
+            &self,
+            _: &TicTacToe,
+            _: &tictactoe::State,
+            _: Player,
+            _: &mut Rng,
+        ) -> Decision<u8> {
+            self.decisions
+                .lock()
+                .unwrap()
+                .pop()
+                .expect("The script should last the whole game")
+        }
+
+        fn cannot_undo(&self, _: &TicTacToe, _: Player) {
+            *self.rejected_undos.lock().unwrap() += 1;
+        }
+
+        fn game_over(&self, _: &TicTacToe, state: &tictactoe::State, _: Player, _: Option<Player>) {
+            *self.final_state.lock().unwrap() = Some(*state);
+        }
+
+        fn learn(&mut self, _: &TicTacToe, _: &mut Rng) {}
+
+        fn save(&self, _: &TicTacToe) -> Result<(), ReLearnError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_undo() {
+        // X can't undo before O moves, and then takes back its move in the center and O's reply.
+        // After that O can't undo either, as X moved only once
+        let player_1 = ScriptedPlayer::new(vec![
+            Decision::Undo,
+            Decision::Play(4),
+            Decision::Undo,
+            Decision::Play(0),
+            Decision::Play(1),
+            Decision::Play(2),
+        ]);
+        let player_2 = ScriptedPlayer::new(vec![
+            Decision::Play(8),
+            Decision::Undo,
+            Decision::Play(3),
+            Decision::Play(6),
+        ]);
+
+        let record = TicTacToe::default().play(
+            &player_1,
+            &player_2,
+            IllegalMovePolicy::Forfeit,
+            &mut Rng::with_seed(0),
+        );
+
+        assert_eq!(record.winner, Some(Player::X));
+        assert_eq!(record.moves, vec![0, 3, 1, 6, 2]);
+        assert_eq!(record.decisions, vec![0, 1, 2, 3, 4]);
+        assert!(record.illegal_moves.is_empty());
+        assert_eq!(*player_1.rejected_undos.lock().unwrap(), 1);
+        assert_eq!(*player_2.rejected_undos.lock().unwrap(), 1);
+        assert_eq!(
+            *player_1.final_state.lock().unwrap(),
+            TicTacToe::default().parse_state("XXX|O--|O--").ok()
+        );
+    }
+}
@@ -0,0 +1,350 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use super::{splitmix64, Game, MoveError, MoveErrorKind, Player, Status};
+
+/// The most heaps that can be encoded exactly in an `u64`, one byte for each heap.
+const MAX_EXACT_HEAPS: usize = 8;
+
+/// Nim, where the players take turns taking any number of pieces from a single heap. With the
+/// normal play rule the player that takes the last piece wins, with the misère rule it loses.
+///
+/// In the subtraction games the number of pieces taken at once must be in the subtraction set
+/// (e.g., 1, 2 or 3), so the game may end with pieces left, when no heap is big enough to take
+/// from. The player that can't move loses (or wins in misère play).
+pub struct Nim {
+    /// The initial size of each heap.
+    heaps: Vec<u8>,
+    /// How many pieces can be taken at once, any number when `None`.
+    subtraction_set: Option<Vec<u8>>,
+    misere: bool,
+    /// The Sprague-Grundy number of a single heap of each size, see `Game::grundy_number`.
+    heap_grundy_numbers: Vec<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct State {
+    heaps: Vec<u8>,
+    /// The players alternate, so it tells the player to move.
+    moves: u32,
+}
+
+/// Takes `count` pieces from the `heap`, written as `<HEAP>:<COUNT>` with the heaps numbered from 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Take {
+    heap: u8,
+    count: u8,
+}
+
+impl Game for Nim {
+    type State = State;
+    type Action = Take;
+
+    fn initial_state(&self) -> State {
+        State {
+            heaps: self.heaps.clone(),
+            moves: 0,
+        }
+    }
+
+    fn status(&self, state: &State) -> Status {
+        if !self.available_moves(state).is_empty() {
+            return Status::OnGoing;
+        }
+
+        // The previous player made the last move
+        let player_to_move = self.current_player(state);
+
+        if self.misere {
+            Status::Finished(Some(player_to_move))
+        } else {
+            Status::Finished(Some(player_to_move.next_player()))
+        }
+    }
+
+    fn available_moves(&self, state: &State) -> Vec<Take> {
+        state
+            .heaps
+            .iter()
+            .enumerate()
+            .flat_map(|(heap, &size)| {
+                self.counts(size).into_iter().map(move |count| Take {
+                    heap: heap as u8,
+                    count,
+                })
+            })
+            .collect()
+    }
+
+    fn act(&self, _: Player, action: Take, state: &mut State) -> Result<(), MoveError<Self>> {
+        let Some(&size) = state.heaps.get(action.heap as usize) else {
+            return Err(MoveError::new(MoveErrorKind::OutOfBound, action, state));
+        };
+
+        if !self.counts(size).contains(&action.count) {
+            return Err(MoveError::new(MoveErrorKind::Illegal, action, state));
+        }
+
+        state.heaps[action.heap as usize] -= action.count;
+        state.moves += 1;
+
+        Ok(())
+    }
+
+    fn current_player(&self, state: &State) -> Player {
+        if state.moves.is_multiple_of(2) {
+            Player::X
+        } else {
+            Player::O
+        }
+    }
+
+    /// The heap sizes separated by `|`, e.g. `3|4|5`. The player to move is always X.
+    ///
+    /// The heaps only shrink, so they can't be bigger than at the start of the game.
+    fn parse_state(&self, board: &str) -> Result<State, String> {
+        let heaps = board
+            .trim()
+            .split('|')
+            .map(|size| {
+                size.parse()
+                    .map_err(|_| format!("Invalid heap size '{size}'"))
+            })
+            .collect::<Result<Vec<u8>, _>>()?;
+
+        if heaps.len() != self.heaps.len() {
+            return Err(format!(
+                "The board '{board}' should have {} heaps",
+                self.heaps.len()
+            ));
+        }
+
+        for (heap, (&size, &initial_size)) in heaps.iter().zip(&self.heaps).enumerate() {
+            if size > initial_size {
+                return Err(format!(
+                    "The heap {} has {size} pieces, more than the {initial_size} it starts with",
+                    heap + 1
+                ));
+            }
+        }
+
+        Ok(State { heaps, moves: 0 })
+    }
+
+    /// The value of a player does not depend on who it is, so the player to move is not encoded.
+    fn encode(&self, state: &State) -> u64 {
+        if state.heaps.len() <= MAX_EXACT_HEAPS {
+            state
+                .heaps
+                .iter()
+                .fold(0, |encoded_state, &size| encoded_state << 8 | size as u64)
+        } else {
+            state.heaps.iter().fold(0, |encoded_state, &size| {
+                splitmix64(encoded_state ^ size as u64)
+            })
+        }
+    }
+
+    /// In normal play it's the nim-sum (xor) of the Sprague-Grundy numbers of the heaps.
+    ///
+    /// The misère play has no such numbers, but in Nim the outcome is the same as in normal play
+    /// while any heap has more than one piece. Once they all have one or none, the state is lost
+    /// for the player to move when the number of heaps with one piece is odd, so we flip the last
+    /// bit of the nim-sum (Bouton). The misère subtraction games have none.
+    fn grundy_number(&self, state: &State) -> Option<u32> {
+        let nim_sum = state.heaps.iter().fold(0, |nim_sum, &size| {
+            nim_sum ^ self.heap_grundy_numbers[size as usize]
+        });
+
+        match (self.misere, &self.subtraction_set) {
+            (false, _) => Some(nim_sum),
+            (true, None) if state.heaps.iter().all(|&size| size <= 1) => Some(nim_sum ^ 1),
+            (true, None) => Some(nim_sum),
+            (true, Some(_)) => None,
+        }
+    }
+
+    fn name(&self) -> String {
+        let heaps = join(&self.heaps, "-");
+        let name = match &self.subtraction_set {
+            Some(subtraction_set) => {
+                format!("subtraction-{heaps}-take-{}", join(subtraction_set, "-"))
+            }
+            None => format!("nim-{heaps}"),
+        };
+
+        if self.misere {
+            name + "-misere"
+        } else {
+            name
+        }
+    }
+}
+
+impl Nim {
+    pub(crate) fn new(heaps: Vec<u8>, subtraction_set: Option<Vec<u8>>, misere: bool) -> Self {
+        let max_size = heaps.iter().copied().max().unwrap_or_default() as usize;
+        let mut heap_grundy_numbers: Vec<u32> = Vec::with_capacity(max_size + 1);
+
+        // The mex (minimum excluded value) of the numbers of the heaps reachable in one move
+        for size in 0..=max_size {
+            let mut reachable = vec![false; size + 1];
+
+            for count in counts(subtraction_set.as_deref(), size as u8) {
+                reachable[heap_grundy_numbers[size - count as usize] as usize] = true;
+            }
+
+            let mex = reachable.iter().take_while(|&&reachable| reachable).count();
+            heap_grundy_numbers.push(mex as u32);
+        }
+
+        Nim {
+            heaps,
+            subtraction_set,
+            misere,
+            heap_grundy_numbers,
+        }
+    }
+
+    fn counts(&self, size: u8) -> Vec<u8> {
+        counts(self.subtraction_set.as_deref(), size)
+    }
+}
+
+/// How many pieces can be taken from a heap with `size` pieces.
+fn counts(subtraction_set: Option<&[u8]>, size: u8) -> Vec<u8> {
+    match subtraction_set {
+        Some(subtraction_set) => subtraction_set
+            .iter()
+            .copied()
+            .filter(|&count| 0 < count && count <= size)
+            .collect(),
+        None => (1..=size).collect(),
+    }
+}
+
+fn join(values: &[u8], separator: &str) -> String {
+    values
+        .iter()
+        .map(u8::to_string)
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+impl Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (heap, &size) in self.heaps.iter().enumerate() {
+            writeln!(f, "{}: {} ({size})", heap + 1, "o".repeat(size as usize))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Take {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.heap + 1, self.count)
+    }
+}
+
+impl FromStr for Take {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("Invalid move '{s}', expected the '<HEAP>:<COUNT>' format");
+
+        let (heap, count) = s.split_once(':').ok_or_else(error)?;
+        let heap: u8 = heap.parse().map_err(|_| error())?;
+
+        Ok(Take {
+            heap: heap.checked_sub(1).ok_or_else(error)?,
+            count: count.parse().map_err(|_| error())?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{game::IllegalMovePolicy, players::RandomPlayer};
+
+    #[test]
+    fn test_act() {
+        let game = Nim::new(vec![1, 3], None, false);
+        let mut state = game.initial_state();
+
+        assert_eq!(
+            game.available_moves(&state)
+                .iter()
+                .map(Take::to_string)
+                .collect::<Vec<_>>(),
+            vec!["1:1", "2:1", "2:2", "2:3"]
+        );
+
+        let error = game
+            .act(Player::X, "1:2".parse().unwrap(), &mut state)
+            .unwrap_err();
+        assert_eq!(error.kind, MoveErrorKind::Illegal);
+        let error = game
+            .act(Player::X, "3:1".parse().unwrap(), &mut state)
+            .unwrap_err();
+        assert_eq!(error.kind, MoveErrorKind::OutOfBound);
+        assert!("0:1".parse::<Take>().is_err());
+
+        assert!(game
+            .act(Player::X, "2:3".parse().unwrap(), &mut state)
+            .is_ok());
+        assert_eq!(state.heaps, vec![1, 0]);
+        assert_eq!(game.current_player(&state), Player::O);
+        assert_eq!(game.status(&state), Status::OnGoing);
+
+        // O takes the last piece
+        let mut misere_state = state.clone();
+        assert!(game
+            .act(Player::O, "1:1".parse().unwrap(), &mut state)
+            .is_ok());
+        assert_eq!(game.status(&state), Status::Finished(Some(Player::O)));
+
+        let misere_game = Nim::new(vec![1, 3], None, true);
+        assert!(misere_game
+            .act(Player::O, "1:1".parse().unwrap(), &mut misere_state)
+            .is_ok());
+        assert_eq!(
+            misere_game.status(&misere_state),
+            Status::Finished(Some(Player::X))
+        );
+    }
+
+    #[test]
+    fn test_subtraction_game() {
+        let game = Nim::new(vec![9], Some(vec![2, 3]), false);
+
+        // The heaps of size 0 and 1 have no moves, and the numbers repeat every 5 sizes
+        assert_eq!(game.heap_grundy_numbers, vec![0, 0, 1, 1, 2, 0, 0, 1, 1, 2]);
+
+        let state = game.parse_state("1").unwrap();
+        assert!(game.available_moves(&state).is_empty());
+        assert_eq!(game.status(&state), Status::Finished(Some(Player::O)));
+
+        let state = game.parse_state("4").unwrap();
+        assert_eq!(
+            game.available_moves(&state),
+            vec![Take { heap: 0, count: 2 }, Take { heap: 0, count: 3 }]
+        );
+        assert!(game.parse_state("4|4").is_err());
+        assert!(game.parse_state("10").is_err());
+        assert!(game.parse_state("200").is_err());
+
+        // No heap is big enough to take from, so X loses before moving
+        let game = Nim::new(vec![1, 1], Some(vec![2, 3]), false);
+        let record = game.play(
+            &RandomPlayer,
+            &RandomPlayer,
+            IllegalMovePolicy::Forfeit,
+            &mut fastrand::Rng::with_seed(0),
+        );
+        assert_eq!(record.winner, Some(Player::O));
+        assert!(record.moves.is_empty());
+    }
+}
@@ -0,0 +1,465 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    field_label, labelled_board, parse_field_label, splitmix64, Game, MoveError, MoveErrorKind,
+    Player, Status,
+};
+
+/// The Zobrist keys of the fields use the indexes up to `2 * 63 + 1`, the key of the player to
+/// move comes right after them.
+const PLAYER_TO_MOVE_KEY: u64 = 128;
+
+/// The value of a corner (used by the heuristic), as it can never be flipped.
+const CORNER_VALUE: i64 = 25;
+/// The value of each available move (used by the heuristic).
+const MOBILITY_VALUE: i64 = 5;
+
+/// Othello (or Reversi), played in a square board of even size. A piece must be placed so it
+/// outflanks a line of the opponent's pieces, which are flipped to the player's color. A player
+/// without such a move must pass, and when neither player can move the one with more pieces wins.
+pub struct Othello {
+    size: u8,
+}
+
+/// The board is stored as one bitboard per player, where the field at `column` and `row` (counting
+/// from the top) is the bit `row * size + column`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct State {
+    boards: [u64; 2],
+    /// As the players may pass, it can't be told by the number of pieces.
+    player: Player,
+    size: u8,
+}
+
+/// A player with no move to place a piece must pass, so the players always alternate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Move {
+    Place(u8),
+    Pass,
+}
+
+impl Game for Othello {
+    type State = State;
+    type Action = Move;
+
+    fn initial_state(&self) -> State {
+        let size = self.size;
+        let center = size / 2;
+        let field = |column: u8, row: u8| 1 << (row * size + column);
+
+        State {
+            boards: [
+                field(center, center - 1) | field(center - 1, center),
+                field(center - 1, center - 1) | field(center, center),
+            ],
+            player: Player::X,
+            size,
+        }
+    }
+
+    fn status(&self, state: &State) -> Status {
+        let player = state.player;
+
+        if state.moves(player) != 0 || state.moves(player.next_player()) != 0 {
+            return Status::OnGoing;
+        }
+
+        let pieces = |player: Player| state.boards[player as usize].count_ones();
+
+        match pieces(Player::X).cmp(&pieces(Player::O)) {
+            std::cmp::Ordering::Greater => Status::Finished(Some(Player::X)),
+            std::cmp::Ordering::Less => Status::Finished(Some(Player::O)),
+            std::cmp::Ordering::Equal => Status::Finished(None),
+        }
+    }
+
+    fn available_moves(&self, state: &State) -> Vec<Move> {
+        let mut moves = state.moves(state.player);
+
+        if moves == 0 {
+            return vec![Move::Pass];
+        }
+
+        let mut available_moves = Vec::with_capacity(moves.count_ones() as usize);
+
+        while moves != 0 {
+            available_moves.push(Move::Place(moves.trailing_zeros() as u8));
+            // Clears the lowest bit
+            moves &= moves - 1;
+        }
+
+        available_moves
+    }
+
+    fn act(&self, player: Player, action: Move, state: &mut State) -> Result<(), MoveError<Self>> {
+        let error = |kind| Err(MoveError::new(kind, action, state));
+
+        match action {
+            Move::Pass if state.moves(player) != 0 => return error(MoveErrorKind::Illegal),
+            Move::Pass => {}
+            Move::Place(position) => {
+                if position >= self.size * self.size {
+                    return error(MoveErrorKind::OutOfBound);
+                }
+
+                let field = 1 << position;
+
+                if state.occupied() & field != 0 {
+                    return error(MoveErrorKind::NonEmptyField);
+                }
+
+                let flips = state.flips(player, field);
+
+                if flips == 0 {
+                    return error(MoveErrorKind::Illegal);
+                }
+
+                state.boards[player as usize] |= field | flips;
+                state.boards[player.next_player() as usize] &= !flips;
+            }
+        }
+
+        state.player = player.next_player();
+
+        Ok(())
+    }
+
+    fn current_player(&self, state: &State) -> Player {
+        state.player
+    }
+
+    fn encode(&self, state: &State) -> u64 {
+        let mut encoded_state = match state.player {
+            Player::X => 0,
+            Player::O => splitmix64(PLAYER_TO_MOVE_KEY),
+        };
+
+        for player in [Player::X, Player::O] {
+            let mut board = state.boards[player as usize];
+
+            while board != 0 {
+                let position = board.trailing_zeros() as u64;
+                encoded_state ^= splitmix64(2 * position + player as u64);
+                board &= board - 1;
+            }
+        }
+
+        encoded_state
+    }
+
+    fn label(&self, action: Move) -> String {
+        match action {
+            Move::Place(position) => field_label(position as usize, self.size as usize),
+            Move::Pass => action.to_string(),
+        }
+    }
+
+    fn parse_label(&self, label: &str) -> Option<Move> {
+        if label == "pass" {
+            return Some(Move::Pass);
+        }
+
+        parse_field_label(label, self.size as usize, self.size as usize)
+            .map(|position| Move::Place(position as u8))
+    }
+
+    fn name(&self) -> String {
+        match self.size {
+            8 => "othello".to_string(),
+            size => format!("othello-{size}x{size}"),
+        }
+    }
+
+    /// The corners the player holds, as they can't be flipped, and how many more moves than the
+    /// opponent it has.
+    fn heuristic(&self, state: &State, player: Player) -> i64 {
+        let opponent = player.next_player();
+        let corners = state.corners();
+        let corner_count =
+            |player: Player| (state.boards[player as usize] & corners).count_ones() as i64;
+        let mobility = |player: Player| state.moves(player).count_ones() as i64;
+
+        CORNER_VALUE * (corner_count(player) - corner_count(opponent))
+            + MOBILITY_VALUE * (mobility(player) - mobility(opponent))
+    }
+}
+
+impl Othello {
+    /// The board must have an even size, up to 8x8 so it fits in a bitboard.
+    pub(crate) fn new(size: u8) -> Self {
+        assert!(
+            size.is_multiple_of(2) && (4..=8).contains(&size),
+            "The Othello board size must be 4, 6 or 8"
+        );
+
+        Othello { size }
+    }
+}
+
+impl State {
+    fn occupied(&self) -> u64 {
+        self.boards[0] | self.boards[1]
+    }
+
+    fn full_board(&self) -> u64 {
+        u64::MAX >> (64 - self.size as u32 * self.size as u32)
+    }
+
+    fn corners(&self) -> u64 {
+        let last = self.size - 1;
+
+        [0, last, last * self.size, last * self.size + last]
+            .into_iter()
+            .fold(0, |corners, position| corners | 1 << position)
+    }
+
+    /// The fields where the `player` can place a piece, one bit for each field.
+    fn moves(&self, player: Player) -> u64 {
+        let own = self.boards[player as usize];
+        let opponent = self.boards[player.next_player() as usize];
+        let empty = !self.occupied() & self.full_board();
+        let mut moves = 0;
+
+        for direction in Direction::ALL {
+            // The lines of the opponent's pieces that start next to one of the player's pieces
+            let mut line = self.shift(own, direction) & opponent;
+
+            // A line has up to `size - 2` pieces
+            for _ in 0..self.size - 3 {
+                line |= self.shift(line, direction) & opponent;
+            }
+
+            moves |= self.shift(line, direction) & empty;
+        }
+
+        moves
+    }
+
+    /// The opponent's pieces flipped by placing a piece of the `player` in the `field`.
+    fn flips(&self, player: Player, field: u64) -> u64 {
+        let own = self.boards[player as usize];
+        let opponent = self.boards[player.next_player() as usize];
+        let mut flips = 0;
+
+        for direction in Direction::ALL {
+            let mut line = 0;
+            let mut next_field = self.shift(field, direction);
+
+            while next_field & opponent != 0 {
+                line |= next_field;
+                next_field = self.shift(next_field, direction);
+            }
+
+            // The line is only flipped when it's closed by one of the player's pieces
+            if next_field & own != 0 {
+                flips |= line;
+            }
+        }
+
+        flips
+    }
+
+    /// Moves every field one step in the `direction`, the fields that would leave the board (or
+    /// wrap around to the other side) are dropped.
+    fn shift(&self, fields: u64, direction: Direction) -> u64 {
+        let size = self.size as u32;
+        let first_column = (0..size).fold(0, |mask, row| mask | 1 << (row * size));
+        let last_column = first_column << (size - 1);
+        let full_board = self.full_board();
+        let (not_first_column, not_last_column) =
+            (full_board & !first_column, full_board & !last_column);
+
+        match direction {
+            Direction::East => (fields << 1) & not_first_column,
+            Direction::West => (fields >> 1) & not_last_column,
+            Direction::South => (fields << size) & full_board,
+            Direction::North => fields >> size,
+            Direction::SouthEast => (fields << (size + 1)) & not_first_column,
+            Direction::SouthWest => (fields << (size - 1)) & not_last_column,
+            Direction::NorthEast => (fields >> (size - 1)) & not_first_column,
+            Direction::NorthWest => (fields >> (size + 1)) & not_last_column,
+        }
+    }
+
+    fn field(&self, position: usize) -> Option<Player> {
+        let field = 1 << position;
+
+        if self.boards[Player::X as usize] & field != 0 {
+            Some(Player::X)
+        } else if self.boards[Player::O as usize] & field != 0 {
+            Some(Player::O)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+}
+
+impl Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let size = self.size as usize;
+
+        write!(
+            f,
+            "{}",
+            labelled_board(size, size, |position| self.field(position))
+        )
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Move::Place(position) => write!(f, "{position}"),
+            Move::Pass => write!(f, "pass"),
+        }
+    }
+}
+
+impl FromStr for Move {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pass" => Ok(Move::Pass),
+            _ => s
+                .parse()
+                .map(Move::Place)
+                .map_err(|_| format!("Invalid move '{s}', expected a field or 'pass'")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    macro_rules! state {
+        ($player:ident: $($s:tt)+) => {{
+            let fields = fields![$($s)+];
+            let size = (fields.len() as f64).sqrt() as u8;
+            let mut boards = [0; 2];
+
+            for (position, field) in fields.into_iter().enumerate() {
+                if let Some(player) = field {
+                    boards[player as usize] |= 1 << position;
+                }
+            }
+
+            State {
+                boards,
+                player: Player::$player,
+                size,
+            }
+        }};
+    }
+
+    fn labels(game: &Othello, state: &State) -> Vec<String> {
+        game.available_moves(state)
+            .into_iter()
+            .map(|action| game.label(action))
+            .collect()
+    }
+
+    #[test]
+    fn test_act() {
+        let game = Othello::new(8);
+        let mut state = game.initial_state();
+
+        assert_eq!(labels(&game, &state), vec!["d3", "c4", "f5", "e6"]);
+
+        let d3 = game.parse_label("d3").unwrap();
+        assert!(game.act(Player::X, d3, &mut state).is_ok());
+        assert_eq!(state.boards[Player::X as usize].count_ones(), 4);
+        assert_eq!(state.boards[Player::O as usize].count_ones(), 1);
+        assert_eq!(game.current_player(&state), Player::O);
+
+        let error = game.act(Player::O, d3, &mut state).unwrap_err();
+        assert_eq!(error.kind, MoveErrorKind::NonEmptyField);
+
+        // It doesn't outflank any piece
+        let error = game.act(Player::O, Move::Place(0), &mut state).unwrap_err();
+        assert_eq!(error.kind, MoveErrorKind::Illegal);
+        assert_eq!(error.state, state);
+
+        // It can't pass while it has moves
+        let error = game.act(Player::O, Move::Pass, &mut state).unwrap_err();
+        assert_eq!(error.kind, MoveErrorKind::Illegal);
+
+        let error = game
+            .act(Player::O, Move::Place(64), &mut state)
+            .unwrap_err();
+        assert_eq!(error.kind, MoveErrorKind::OutOfBound);
+    }
+
+    #[test]
+    fn test_flips() {
+        let game = Othello::new(4);
+        let mut state = state![O:
+            X O - -
+            - X - -
+            O - X X
+            O X - -
+        ];
+
+        // Flips the line to the west, but not the open line to the north nor the line to the
+        // north-east, which would wrap around the board
+        assert!(game.act(Player::O, Move::Place(14), &mut state).is_ok());
+        assert_eq!(
+            state,
+            state![X:
+                X O - -
+                - X - -
+                O - X X
+                O O O -
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pass() {
+        let game = Othello::new(4);
+        let mut state = state![X:
+            O X - -
+            - - - -
+            - - - -
+            - - - -
+        ];
+
+        // X can't outflank the corner, so it must pass
+        assert_eq!(game.status(&state), Status::OnGoing);
+        assert_eq!(game.available_moves(&state), vec![Move::Pass]);
+        assert!(game.act(Player::X, Move::Pass, &mut state).is_ok());
+        assert_eq!(game.current_player(&state), Player::O);
+
+        // Neither player can move after O takes the last X piece
+        assert!(game.act(Player::O, Move::Place(2), &mut state).is_ok());
+        assert_eq!(game.status(&state), Status::Finished(Some(Player::O)));
+    }
+}
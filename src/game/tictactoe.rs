@@ -0,0 +1,566 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    field_label, labelled_board, parse_field_label, parse_fields, Game, MoveError, MoveErrorKind,
+    Player, Status, Symmetry,
+};
+
+/// A basic game implementation (Tic-Tac-Toe). In the misère variant the player that lines up three
+/// pieces loses instead.
+#[derive(Default)]
+pub struct TicTacToe {
+    misere: bool,
+}
+
+/// Where each field goes in each symmetry of the board, indexed by `Symmetry::index`.
+const PERMUTATIONS: [[u8; 9]; 8] = permutations();
+
+/// The fields of each line (rows, columns and diagonals), the bit `N` is the field `N`.
+const LINES: [u16; 8] = [
+    0b000_000_111,
+    0b000_111_000,
+    0b111_000_000,
+    0b001_001_001,
+    0b010_010_010,
+    0b100_100_100,
+    0b100_010_001,
+    0b001_010_100,
+];
+const FULL_BOARD: u16 = 0b111_111_111;
+const POWERS_OF_THREE: [u16; 9] = [1, 3, 9, 27, 81, 243, 729, 2187, 6561];
+
+/// The board is stored as a 9-bit mask for each player, where the bit `N` is set when the player
+/// has a piece in the field `N`. It's small enough to be copied around, so the searches don't
+/// allocate anything.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct State {
+    /// Indexed by `Player as usize`.
+    boards: [u16; 2],
+    pub encoded_state: u16,
+}
+
+impl Game for TicTacToe {
+    type State = State;
+    type Action = u8;
+
+    fn initial_state(&self) -> State {
+        State::new()
+    }
+
+    fn status(&self, state: &State) -> Status {
+        if let Some(winner) = TicTacToe::winner(state) {
+            if self.misere {
+                Status::Finished(Some(winner.next_player()))
+            } else {
+                Status::Finished(Some(winner))
+            }
+        } else if state.empty_fields() == 0 {
+            Status::Finished(None)
+        } else {
+            Status::OnGoing
+        }
+    }
+
+    fn available_moves(&self, state: &State) -> Vec<u8> {
+        state.moves().collect()
+    }
+
+    /// For now we'll keep this method as fallible for debugging purpose, we might implement a
+    /// `unchecked_act` in the future for optimization purpose.
+    fn act(&self, player: Player, position: u8, state: &mut State) -> Result<(), MoveError<Self>> {
+        state
+            .act(player, position)
+            .map_err(|kind| MoveError::new(kind, position, state))
+    }
+
+    fn can_undo(&self) -> bool {
+        true
+    }
+
+    fn undo(&self, player: Player, position: u8, state: &mut State) {
+        state.undo(player, position);
+    }
+
+    fn current_player(&self, state: &State) -> Player {
+        if state.empty_fields().count_ones() % 2 == 1 {
+            Player::X
+        } else {
+            Player::O
+        }
+    }
+
+    fn parse_state(&self, board: &str) -> Result<State, String> {
+        let fields = parse_fields(board, 3, 3)?;
+
+        // SAFETY: The fields were parsed from a 3x3 board
+        Ok(State::from_array(unsafe {
+            fields.try_into().unwrap_unchecked()
+        }))
+    }
+
+    fn encode(&self, state: &State) -> u64 {
+        state.encoded_state as u64
+    }
+
+    fn canonical_encode(&self, state: &State) -> (u64, Symmetry) {
+        let (encoded_state, symmetry) = state.canonical();
+
+        (encoded_state as u64, symmetry)
+    }
+
+    fn transform_action(&self, action: u8, symmetry: Symmetry) -> u8 {
+        PERMUTATIONS[symmetry.index()][action as usize]
+    }
+
+    fn label(&self, position: u8) -> String {
+        field_label(position as usize, 3)
+    }
+
+    fn parse_label(&self, label: &str) -> Option<u8> {
+        parse_field_label(label, 3, 3).map(|position| position as u8)
+    }
+
+    fn labelled_board(&self, state: &State) -> String {
+        labelled_board(3, 3, |position| state.field(position as u8))
+    }
+
+    fn name(&self) -> String {
+        if self.misere {
+            "tic-tac-toe-misere".to_string()
+        } else {
+            "tic-tac-toe".to_string()
+        }
+    }
+}
+
+impl TicTacToe {
+    pub(crate) fn new(misere: bool) -> Self {
+        TicTacToe { misere }
+    }
+
+    /// The owner of the pieces lined up, whatever the rules say about it.
+    pub(crate) fn winner(state: &State) -> Option<Player> {
+        [Player::X, Player::O].into_iter().find(|&player| {
+            let board = state.boards[player as usize];
+
+            LINES.iter().any(|&line| line & !board == 0)
+        })
+    }
+}
+
+impl State {
+    pub(crate) fn new() -> Self {
+        State {
+            boards: [0; 2],
+            encoded_state: 0,
+        }
+    }
+
+    pub(super) fn from_array(fields: [Option<Player>; 9]) -> Self {
+        let mut state = State::new();
+
+        for (position, field) in fields.into_iter().enumerate() {
+            if let Some(player) = field {
+                state.place(player, position as u8);
+            }
+        }
+
+        state
+    }
+
+    /// The smallest encoding among the 8 symmetric boards (rotations and reflections) and the
+    /// symmetry that turns this board into it.
+    pub fn canonical(&self) -> (u16, Symmetry) {
+        let canonical = Symmetry::ALL
+            .into_iter()
+            .map(|symmetry| {
+                let encoded_state = (0..9)
+                    .map(|position| {
+                        let value = self.field(position).map_or(0, State::value);
+
+                        value
+                            * POWERS_OF_THREE
+                                [PERMUTATIONS[symmetry.index()][position as usize] as usize]
+                    })
+                    .sum();
+
+                (encoded_state, symmetry)
+            })
+            .min_by_key(|&(encoded_state, _)| encoded_state);
+
+        // SAFETY: There are always 8 symmetries
+        unsafe { canonical.unwrap_unchecked() }
+    }
+
+    pub(super) fn field(&self, position: u8) -> Option<Player> {
+        let bit = 1 << position;
+
+        if self.boards[Player::X as usize] & bit != 0 {
+            Some(Player::X)
+        } else if self.boards[Player::O as usize] & bit != 0 {
+            Some(Player::O)
+        } else {
+            None
+        }
+    }
+
+    /// The empty fields, in ascending order and without allocating.
+    pub(crate) fn moves(&self) -> impl Iterator<Item = u8> {
+        let mut empty_fields = self.empty_fields();
+
+        std::iter::from_fn(move || {
+            if empty_fields == 0 {
+                return None;
+            }
+
+            let position = empty_fields.trailing_zeros() as u8;
+            // Clears the lowest bit
+            empty_fields &= empty_fields - 1;
+
+            Some(position)
+        })
+    }
+
+    /// Reverts the move of the `player` in the `position`, which must be the last one played, so
+    /// the searches can make and unmake moves in place.
+    pub(crate) fn undo(&mut self, player: Player, position: u8) {
+        debug_assert_eq!(self.field(position), Some(player));
+
+        self.boards[player as usize] &= !(1 << position);
+        self.encoded_state -= State::value(player) * POWERS_OF_THREE[position as usize];
+    }
+
+    fn empty_fields(&self) -> u16 {
+        !(self.boards[0] | self.boards[1]) & FULL_BOARD
+    }
+
+    pub(super) fn act(&mut self, player: Player, position: u8) -> Result<(), MoveErrorKind> {
+        if position >= 9 {
+            return Err(MoveErrorKind::OutOfBound);
+        };
+
+        if self.empty_fields() & (1 << position) == 0 {
+            return Err(MoveErrorKind::NonEmptyField);
+        }
+
+        self.place(player, position);
+
+        Ok(())
+    }
+
+    fn place(&mut self, player: Player, position: u8) {
+        self.boards[player as usize] |= 1 << position;
+
+        // Since there is three possible states [None, Player::X, Player::0] for each field, we give
+        // an arbitrary number for each state [0, 1, 2] and offset it by 3 ** N, where N is the
+        // field position. This way the state is matched to a single unique u16.
+        self.encoded_state += State::value(player) * POWERS_OF_THREE[position as usize];
+    }
+
+    fn value(player: Player) -> u16 {
+        match player {
+            Player::O => 2,
+            Player::X => 1,
+        }
+    }
+}
+
+const fn permutations() -> [[u8; 9]; 8] {
+    let mut permutations = [[0; 9]; 8];
+
+    let mut index = 0;
+    while index < 8 {
+        let mut position = 0;
+        while position < 9 {
+            let (column, row) = Symmetry::ALL[index].apply(position % 3, position / 3, 3);
+            permutations[index][position] = (row * 3 + column) as u8;
+            position += 1;
+        }
+        index += 1;
+    }
+
+    permutations
+}
+
+impl Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for i in 0..9 {
+            match self.field(i) {
+                Some(player) => write!(f, " {player} ")?,
+                None => write!(f, "   ")?,
+            };
+
+            if i % 3 < 2 {
+                write!(f, "|")?;
+            } else {
+                writeln!(f)?;
+
+                if i == 8 {
+                    break;
+                }
+
+                writeln!(f, "---+---+---")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    macro_rules! state {
+        ($($s:tt)+) => {
+            State::from_array(fields![$($s)+])
+        };
+    }
+
+    #[test]
+    fn test_act() {
+        let mut state = state![
+            X O -
+            - - -
+            - - -
+        ];
+
+        assert!(TicTacToe::default().act(Player::X, 3, &mut state).is_ok());
+        assert_eq!(
+            state,
+            state![
+                X O -
+                X - -
+                - - -
+            ]
+        );
+        assert_eq!(
+            TicTacToe::default().available_moves(&state),
+            vec![2, 4, 5, 6, 7, 8]
+        );
+
+        assert!(TicTacToe::default().act(Player::X, 0, &mut state).is_err());
+
+        assert!(TicTacToe::default().act(Player::O, 4, &mut state).is_ok());
+        assert_eq!(
+            state,
+            state![
+            X O -
+            X O -
+            - - -
+            ]
+        );
+        assert_eq!(
+            TicTacToe::default().available_moves(&state),
+            vec![2, 5, 6, 7, 8]
+        );
+
+        assert!(TicTacToe::default().act(Player::X, 8, &mut state).is_ok());
+        assert_eq!(
+            state,
+            state![
+            X O -
+            X O -
+            - - X
+            ]
+        );
+        assert_eq!(
+            TicTacToe::default().available_moves(&state),
+            vec![2, 5, 6, 7]
+        );
+
+        assert!(TicTacToe::default().act(Player::O, 7, &mut state).is_ok());
+        assert_eq!(
+            state,
+            state![
+            X O -
+            X O -
+            - O X
+            ]
+        );
+        assert_eq!(TicTacToe::default().available_moves(&state), vec![2, 5, 6]);
+        assert_eq!(
+            TicTacToe::default().status(&state),
+            Status::Finished(Some(Player::O))
+        );
+
+        state.undo(Player::O, 7);
+        assert_eq!(
+            state,
+            state![
+            X O -
+            X O -
+            - - X
+            ]
+        );
+        assert_eq!(TicTacToe::default().status(&state), Status::OnGoing);
+    }
+
+    #[test]
+    fn test_canonical() {
+        let state = state![
+            X O -
+            - X -
+            - - -
+        ];
+        let (encoded_state, symmetry) = state.canonical();
+
+        // The rotations and reflections of the board share the canonical encoding
+        for symmetric_state in [
+            state![
+                - - X
+                - X O
+                - - -
+            ],
+            state![
+                - - -
+                - X -
+                - O X
+            ],
+            state![
+                - O X
+                - X -
+                - - -
+            ],
+        ] {
+            assert_eq!(symmetric_state.canonical().0, encoded_state);
+        }
+
+        assert_ne!(
+            state![
+                X - O
+                - X -
+                - - -
+            ]
+            .canonical()
+            .0,
+            encoded_state
+        );
+
+        // The symmetry moves each field to its place in the canonical board
+        let canonical_state = State::from_array(std::array::from_fn(|position| {
+            let position = (0..9)
+                .find(|&field| {
+                    TicTacToe::default().transform_action(field, symmetry) == position as u8
+                })
+                .unwrap();
+            state.field(position)
+        }));
+        assert_eq!(canonical_state.encoded_state, encoded_state);
+
+        for action in 0..9 {
+            let transformed_action = TicTacToe::default().transform_action(action, symmetry);
+            assert_eq!(
+                TicTacToe::default().transform_action(transformed_action, symmetry.inverse()),
+                action
+            );
+        }
+    }
+
+    #[test]
+    fn test_labels() {
+        for position in 0..9 {
+            assert_eq!(
+                TicTacToe::default().parse_label(&TicTacToe::default().label(position)),
+                Some(position)
+            );
+        }
+
+        assert_eq!(TicTacToe::default().label(5), "c2");
+        assert_eq!(TicTacToe::default().parse_label("B3"), Some(7));
+        assert_eq!(TicTacToe::default().parse_label("4"), Some(4));
+        assert_eq!(TicTacToe::default().parse_label("d1"), None);
+        assert_eq!(TicTacToe::default().parse_label("a4"), None);
+        assert_eq!(TicTacToe::default().parse_label("9"), None);
+
+        assert_eq!(
+            TicTacToe::default().labelled_board(&state![
+                X - -
+                - O -
+                - - -
+            ]),
+            "    a   b   c\n 1  X |   |   \n   ---+---+---\n 2    | O |   \n   ---+---+---\n 3    |   |   \n"
+        );
+    }
+
+    #[test]
+    fn test_parse_state() {
+        let state = TicTacToe::default().parse_state("XO-|-X-|--O").unwrap();
+
+        assert_eq!(
+            state,
+            state![
+                X O -
+                - X -
+                - - O
+            ]
+        );
+        assert_eq!(TicTacToe::default().current_player(&state), Player::X);
+
+        assert!(TicTacToe::default().parse_state("XO-|-X-|--").is_err());
+        assert!(TicTacToe::default().parse_state("XO-|-X-|--A").is_err());
+        assert!(TicTacToe::default().parse_state("XX-|-X-|--O").is_err());
+    }
+
+    #[test]
+    fn test_status() {
+        assert_eq!(
+            TicTacToe::default().status(&state![
+                X X X
+                O O -
+                - - -
+            ]),
+            Status::Finished(Some(Player::X))
+        );
+        assert_eq!(
+            TicTacToe::default().status(&state![
+                X - X
+                O O -
+                - - -
+            ]),
+            Status::OnGoing
+        );
+        assert_eq!(
+            TicTacToe::default().status(&state![
+                O X X
+                O - -
+                O X -
+            ]),
+            Status::Finished(Some(Player::O))
+        );
+        assert_eq!(
+            TicTacToe::default().status(&state![
+                O X O
+                - X -
+                O X -
+            ]),
+            Status::Finished(Some(Player::X))
+        );
+        assert_eq!(
+            TicTacToe::default().status(&state![
+                O X X
+                O X -
+                X O -
+            ]),
+            Status::Finished(Some(Player::X))
+        );
+        assert_eq!(
+            TicTacToe::default().status(&state![
+                X O X
+                O O X
+                - - -
+            ]),
+            Status::OnGoing
+        );
+        assert_eq!(
+            TicTacToe::default().status(&state![
+                X O X
+                O X X
+                O X O
+            ]),
+            Status::Finished(None)
+        );
+    }
+}
@@ -0,0 +1,339 @@
+use std::fmt::Display;
+
+use super::{
+    splitmix64,
+    tictactoe::{self, TicTacToe},
+    Game, MoveError, MoveErrorKind, Player, Status,
+};
+
+/// The Zobrist keys of the fields use the indexes up to `2 * 80 + 2`, the keys for the board where
+/// the next move must be played come right after them.
+const NEXT_BOARD_KEY: u64 = 2 * 81 + 1;
+
+/// Ultimate Tic-Tac-Toe, a Tic-Tac-Toe board where each field is a Tic-Tac-Toe board on its own.
+///
+/// The field where a player moves in a small board decides which small board the opponent must play
+/// in next. Winning a small board takes the respective field in the big board, and the first player
+/// to win the big board wins the game. When the opponent is sent to a board that is already
+/// finished (won or full), they may play in any unfinished board.
+///
+/// The actions are numbered from `0` to `80`, as `9 * board + field`, where both the boards and
+/// the fields are numbered like in Tic-Tac-Toe.
+pub struct UltimateTicTacToe;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct State {
+    boards: [tictactoe::State; 9],
+    /// The big board, where each field is taken by the winner of the respective small board.
+    board: tictactoe::State,
+    next_board: Option<u8>,
+    encoded_state: u64,
+}
+
+impl Game for UltimateTicTacToe {
+    type State = State;
+    type Action = u8;
+
+    fn initial_state(&self) -> State {
+        State {
+            boards: std::array::from_fn(|_| tictactoe::State::new()),
+            board: tictactoe::State::new(),
+            next_board: None,
+            encoded_state: 0,
+        }
+    }
+
+    fn status(&self, state: &State) -> Status {
+        let winner = TicTacToe::winner(&state.board);
+
+        if winner.is_some() {
+            Status::Finished(winner)
+        } else if (0..9).all(|board| state.is_finished(board)) {
+            Status::Finished(None)
+        } else {
+            Status::OnGoing
+        }
+    }
+
+    fn available_moves(&self, state: &State) -> Vec<u8> {
+        let boards = match state.next_board {
+            Some(board) => board..board + 1,
+            None => 0..9,
+        };
+
+        boards
+            .filter(|&board| !state.is_finished(board))
+            .flat_map(|board| {
+                TicTacToe::default()
+                    .available_moves(&state.boards[board as usize])
+                    .into_iter()
+                    .map(move |field| 9 * board + field)
+            })
+            .collect()
+    }
+
+    fn act(&self, player: Player, action: u8, state: &mut State) -> Result<(), MoveError<Self>> {
+        if action >= 81 {
+            return Err(MoveError::new(MoveErrorKind::OutOfBound, action, state));
+        }
+
+        let (board, field) = (action / 9, action % 9);
+
+        if state
+            .next_board
+            .is_some_and(|next_board| next_board != board)
+            || state.is_finished(board)
+        {
+            return Err(MoveError::new(MoveErrorKind::Illegal, action, state));
+        }
+
+        TicTacToe::default()
+            .act(player, field, &mut state.boards[board as usize])
+            .map_err(|error| MoveError::new(error.kind, action, state))?;
+        state.encoded_state ^= State::field_key(player, action);
+
+        if let Some(winner) = TicTacToe::winner(&state.boards[board as usize]) {
+            // SAFETY: The board was not finished before this move, so its field is empty
+            unsafe {
+                TicTacToe::default()
+                    .act(winner, board, &mut state.board)
+                    .unwrap_unchecked()
+            };
+        }
+
+        if let Some(next_board) = state.next_board {
+            state.encoded_state ^= splitmix64(NEXT_BOARD_KEY + next_board as u64);
+        }
+
+        state.next_board = if state.is_finished(field) {
+            None
+        } else {
+            state.encoded_state ^= splitmix64(NEXT_BOARD_KEY + field as u64);
+            Some(field)
+        };
+
+        Ok(())
+    }
+
+    fn current_player(&self, state: &State) -> Player {
+        let empty_fields: usize = state
+            .boards
+            .iter()
+            .map(|board| TicTacToe::default().available_moves(board).len())
+            .sum();
+
+        if (81 - empty_fields).is_multiple_of(2) {
+            Player::X
+        } else {
+            Player::O
+        }
+    }
+
+    /// There are way too many states to encode them exactly in an `u64`, so we use a Zobrist hash.
+    fn encode(&self, state: &State) -> u64 {
+        state.encoded_state
+    }
+
+    fn name(&self) -> String {
+        "ultimate-tic-tac-toe".to_string()
+    }
+
+    /// The difference between the number of small boards won by each player.
+    fn heuristic(&self, state: &State, player: Player) -> i64 {
+        (0..9)
+            .filter_map(|board| state.board.field(board))
+            .map(|winner| if winner == player { 10 } else { -10 })
+            .sum()
+    }
+}
+
+impl State {
+    #[cfg(test)]
+    fn from_boards(boards: [tictactoe::State; 9], next_board: Option<u8>) -> Self {
+        let mut state = UltimateTicTacToe.initial_state();
+
+        for (board, small_board) in boards.iter().enumerate() {
+            for field in 0..9 {
+                if let Some(player) = small_board.field(field) {
+                    state.encoded_state ^= State::field_key(player, 9 * board as u8 + field);
+                }
+            }
+
+            if let Some(winner) = TicTacToe::winner(small_board) {
+                TicTacToe::default()
+                    .act(winner, board as u8, &mut state.board)
+                    .unwrap();
+            }
+        }
+
+        if let Some(next_board) = next_board {
+            state.encoded_state ^= splitmix64(NEXT_BOARD_KEY + next_board as u64);
+        }
+
+        state.boards = boards;
+        state.next_board = next_board;
+        state
+    }
+
+    fn is_finished(&self, board: u8) -> bool {
+        TicTacToe::default().status(&self.boards[board as usize]) != Status::OnGoing
+    }
+
+    fn field_key(player: Player, action: u8) -> u64 {
+        let value = match player {
+            Player::X => 1,
+            Player::O => 2,
+        };
+
+        splitmix64(action as u64 * 2 + value)
+    }
+}
+
+impl Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in 0..9 {
+            if row > 0 && row % 3 == 0 {
+                writeln!(f, "-------+-------+-------")?;
+            }
+
+            for column in 0..9 {
+                if column > 0 && column % 3 == 0 {
+                    write!(f, " |")?;
+                }
+
+                let board = (row / 3) * 3 + column / 3;
+                let field = (row % 3) * 3 + column % 3;
+
+                match self.boards[board].field(field as u8) {
+                    Some(player) => write!(f, " {player}")?,
+                    None => write!(f, " .")?,
+                }
+            }
+
+            writeln!(f)?;
+        }
+
+        match self.next_board {
+            Some(board) => writeln!(f, "Next board: {board}"),
+            None => writeln!(f, "Next board: any"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    macro_rules! board {
+        ($($s:tt)+) => {
+            tictactoe::State::from_array(fields![$($s)+])
+        };
+    }
+
+    #[test]
+    fn test_act() {
+        let mut state = UltimateTicTacToe.initial_state();
+
+        assert!(UltimateTicTacToe.act(Player::X, 4, &mut state).is_ok());
+        assert_eq!(
+            UltimateTicTacToe.available_moves(&state),
+            (36..45).collect::<Vec<_>>()
+        );
+
+        // The move must be in the board decided by the last move
+        assert!(UltimateTicTacToe.act(Player::O, 0, &mut state).is_err());
+        assert!(UltimateTicTacToe.act(Player::O, 81, &mut state).is_err());
+
+        assert!(UltimateTicTacToe.act(Player::O, 40, &mut state).is_ok());
+        assert!(UltimateTicTacToe.act(Player::X, 40, &mut state).is_err());
+        assert_eq!(UltimateTicTacToe.available_moves(&state).len(), 8);
+
+        let empty = board![
+            - - -
+            - - -
+            - - -
+        ];
+        let won = board![
+            X X X
+            O O -
+            - - -
+        ];
+        let mut boards: [tictactoe::State; 9] = [empty; 9];
+        boards[0] = won;
+        let mut state = State::from_boards(boards, None);
+
+        // Sending the opponent to a finished board let them play in any other board
+        assert!(UltimateTicTacToe.act(Player::O, 9, &mut state).is_ok());
+        assert_eq!(UltimateTicTacToe.available_moves(&state).len(), 71);
+        assert!(UltimateTicTacToe.act(Player::X, 8, &mut state).is_err());
+        assert!(UltimateTicTacToe.act(Player::X, 80, &mut state).is_ok());
+    }
+
+    #[test]
+    fn test_status() {
+        let empty = board![
+            - - -
+            - - -
+            - - -
+        ];
+        let won = board![
+            X X X
+            O O -
+            - - -
+        ];
+        let draw = board![
+            X O X
+            O X X
+            O X O
+        ];
+
+        let mut boards: [tictactoe::State; 9] = [empty; 9];
+        boards[0] = won;
+        boards[1] = won;
+        assert_eq!(
+            UltimateTicTacToe.status(&State::from_boards(boards, None)),
+            Status::OnGoing
+        );
+
+        boards[2] = won;
+        assert_eq!(
+            UltimateTicTacToe.status(&State::from_boards(boards, None)),
+            Status::Finished(Some(Player::X))
+        );
+
+        let mut boards: [tictactoe::State; 9] = [draw; 9];
+        assert_eq!(
+            UltimateTicTacToe.status(&State::from_boards(boards, None)),
+            Status::Finished(None)
+        );
+
+        boards[4] = won;
+        assert_eq!(
+            UltimateTicTacToe.status(&State::from_boards(boards, None)),
+            Status::Finished(None)
+        );
+    }
+
+    #[test]
+    fn test_encode() {
+        let mut state = UltimateTicTacToe.initial_state();
+        let mut other = UltimateTicTacToe.initial_state();
+
+        assert!(UltimateTicTacToe.act(Player::X, 4, &mut state).is_ok());
+        assert!(UltimateTicTacToe.act(Player::X, 5, &mut other).is_ok());
+        assert_ne!(
+            UltimateTicTacToe.encode(&state),
+            UltimateTicTacToe.encode(&other)
+        );
+
+        let boards = state.boards;
+        assert_eq!(
+            UltimateTicTacToe.encode(&State::from_boards(boards, Some(4))),
+            UltimateTicTacToe.encode(&state)
+        );
+        assert_ne!(
+            UltimateTicTacToe.encode(&State::from_boards(boards, None)),
+            UltimateTicTacToe.encode(&state)
+        );
+    }
+}
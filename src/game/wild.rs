@@ -0,0 +1,242 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    field_label, labelled_board, parse_field_label, parse_pieces,
+    tictactoe::{State, TicTacToe},
+    Game, MoveError, Player, Status, Symmetry,
+};
+
+/// Wild Tic-Tac-Toe, where each player places either piece and the player that lines up three
+/// pieces of the same kind wins, whoever placed the other two. In the misère variant that player
+/// loses instead.
+pub struct WildTicTacToe {
+    misere: bool,
+}
+
+/// Places the `piece` in the `position`, written as the piece followed by the position (e.g.,
+/// `O4`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Placement {
+    position: u8,
+    piece: Player,
+}
+
+impl Game for WildTicTacToe {
+    type State = State;
+    type Action = Placement;
+
+    fn initial_state(&self) -> State {
+        State::new()
+    }
+
+    fn status(&self, state: &State) -> Status {
+        if TicTacToe::winner(state).is_some() {
+            // The game ends as soon as the pieces are lined up, so the last move did it
+            let player_to_move = self.current_player(state);
+
+            if self.misere {
+                Status::Finished(Some(player_to_move))
+            } else {
+                Status::Finished(Some(player_to_move.next_player()))
+            }
+        } else if state.moves().next().is_none() {
+            Status::Finished(None)
+        } else {
+            Status::OnGoing
+        }
+    }
+
+    fn available_moves(&self, state: &State) -> Vec<Placement> {
+        state
+            .moves()
+            .flat_map(|position| {
+                [Player::X, Player::O]
+                    .into_iter()
+                    .map(move |piece| Placement { position, piece })
+            })
+            .collect()
+    }
+
+    fn act(&self, _: Player, action: Placement, state: &mut State) -> Result<(), MoveError<Self>> {
+        state
+            .act(action.piece, action.position)
+            .map_err(|kind| MoveError::new(kind, action, state))
+    }
+
+    /// The players alternate, so it depends only on how many pieces are in the board.
+    fn current_player(&self, state: &State) -> Player {
+        TicTacToe::default().current_player(state)
+    }
+
+    fn parse_state(&self, board: &str) -> Result<State, String> {
+        let fields = parse_pieces(board, 3, 3)?;
+
+        // SAFETY: The fields were parsed from a 3x3 board
+        Ok(State::from_array(unsafe {
+            fields.try_into().unwrap_unchecked()
+        }))
+    }
+
+    fn encode(&self, state: &State) -> u64 {
+        TicTacToe::default().encode(state)
+    }
+
+    fn canonical_encode(&self, state: &State) -> (u64, Symmetry) {
+        TicTacToe::default().canonical_encode(state)
+    }
+
+    fn transform_action(&self, action: Placement, symmetry: Symmetry) -> Placement {
+        Placement {
+            position: TicTacToe::default().transform_action(action.position, symmetry),
+            piece: action.piece,
+        }
+    }
+
+    fn label(&self, action: Placement) -> String {
+        format!(
+            "{}{}",
+            action.piece,
+            field_label(action.position as usize, 3)
+        )
+    }
+
+    fn parse_label(&self, label: &str) -> Option<Placement> {
+        let (piece, field) = split_piece(label)?;
+
+        Some(Placement {
+            position: parse_field_label(field, 3, 3)? as u8,
+            piece,
+        })
+    }
+
+    fn labelled_board(&self, state: &State) -> String {
+        labelled_board(3, 3, |position| state.field(position as u8))
+    }
+
+    fn name(&self) -> String {
+        if self.misere {
+            "wild-tic-tac-toe-misere".to_string()
+        } else {
+            "wild-tic-tac-toe".to_string()
+        }
+    }
+}
+
+impl WildTicTacToe {
+    pub(crate) fn new(misere: bool) -> Self {
+        WildTicTacToe { misere }
+    }
+}
+
+/// Splits the piece (`X` or `O`, in any case) from the beginning of the text.
+fn split_piece(text: &str) -> Option<(Player, &str)> {
+    let piece = match text.get(..1)? {
+        "X" | "x" => Player::X,
+        "O" | "o" => Player::O,
+        _ => return None,
+    };
+
+    Some((piece, &text[1..]))
+}
+
+impl Display for Placement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.piece, self.position)
+    }
+}
+
+impl FromStr for Placement {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error =
+            || format!("Invalid move '{s}', expected the piece and the position (e.g., O4)");
+
+        let (piece, position) = split_piece(s).ok_or_else(error)?;
+
+        Ok(Placement {
+            position: position.parse().map_err(|_| error())?,
+            piece,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    macro_rules! state {
+        ($($s:tt)+) => {
+            State::from_array(fields![$($s)+])
+        };
+    }
+
+    #[test]
+    fn test_act() {
+        let game = WildTicTacToe::new(false);
+        let mut state = state![
+            O O -
+            X - -
+            X - -
+        ];
+
+        assert_eq!(game.current_player(&state), Player::X);
+        assert_eq!(game.available_moves(&state).len(), 10);
+
+        assert!(game
+            .act(Player::X, "X0".parse().unwrap(), &mut state)
+            .is_err());
+        assert!(game
+            .act(Player::X, "X8".parse().unwrap(), &mut state)
+            .is_ok());
+        assert!(game
+            .act(Player::O, "X2".parse().unwrap(), &mut state)
+            .is_ok());
+        assert_eq!(game.status(&state), Status::OnGoing);
+        assert!(game
+            .act(Player::X, "O2".parse().unwrap(), &mut state)
+            .is_err());
+
+        // X lines up its piece with the one placed by O
+        let mut misere_state = state;
+        assert!(game
+            .act(Player::X, "X5".parse().unwrap(), &mut state)
+            .is_ok());
+        assert_eq!(game.status(&state), Status::Finished(Some(Player::X)));
+
+        // In the misère variant lining up the pieces loses
+        let misere_game = WildTicTacToe::new(true);
+        assert!(misere_game
+            .act(Player::X, "X5".parse().unwrap(), &mut misere_state)
+            .is_ok());
+        assert_eq!(
+            misere_game.status(&misere_state),
+            Status::Finished(Some(Player::O))
+        );
+    }
+
+    #[test]
+    fn test_labels() {
+        let game = WildTicTacToe::new(false);
+
+        for action in game.available_moves(&game.initial_state()) {
+            assert_eq!(game.parse_label(&game.label(action)), Some(action));
+        }
+
+        let action = Placement {
+            position: 5,
+            piece: Player::O,
+        };
+        assert_eq!(game.label(action), "Oc2");
+        assert_eq!(action.to_string(), "O5");
+        assert_eq!(game.parse_label("oC2"), Some(action));
+        assert_eq!(game.parse_label("O5"), Some(action));
+        assert_eq!(game.parse_label("c2"), None);
+        assert!("5".parse::<Placement>().is_err());
+
+        // Any number of each piece is allowed
+        assert!(game.parse_state("XX-|-X-|---").is_ok());
+    }
+}
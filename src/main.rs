@@ -2,18 +2,87 @@ mod commands;
 mod game;
 mod players;
 
-use clap::{Parser, Subcommand, ValueEnum};
-use game::Game;
-use players::{minmax, HumanPlayer, MinMaxPlayer, Player, RandomPlayer};
-use std::{fmt, fs::File};
-
-const GAME: Game = Game {};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use commands::{Format, MatchOptions, PlayerDescription, Sprt};
+use fastrand::Rng;
+use game::{
+    Board, ConnectFour, DiceRace, Game, IllegalMovePolicy, KuhnPoker, MnkGame, Nim, Othello,
+    TicTacToe, UltimateTicTacToe, WildTicTacToe,
+};
+use players::{
+    cfr, minmax, qlearning, AlphaBetaPlayer, CfrPlayer, ExpectiminimaxPlayer, HumanPlayer,
+    MctsPlayer, MinMaxPlayer, Player, QLearningParameters, QLearningPlayer, RandomPlayer,
+    SpragueGrundyPlayer,
+};
+use serde::de::DeserializeOwned;
+use std::{
+    fmt,
+    fs::File,
+    path::{Path, PathBuf},
+};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Arguments {
     #[command(subcommand)]
     command: Commands,
+    /// The game to be played or learned
+    #[arg(long, global = true, value_enum, default_value_t = GameKind::TicTacToe)]
+    game: GameKind,
+    /// The board dimensions of the m,n,k-game, in the `<WIDTH>x<HEIGHT>` format
+    #[arg(long, global = true, default_value_t = Board { width: 3, height: 3 })]
+    board: Board,
+    /// How many aligned pieces are needed to win the m,n,k-game
+    #[arg(long, global = true, default_value_t = 3, value_parser = clap::value_parser!(u16).range(1..))]
+    k: u16,
+    /// The initial heap sizes of Nim (3,4,5 by default) or of the subtraction game (21 by default)
+    #[arg(long, global = true, value_delimiter = ',', value_parser = clap::value_parser!(u8).range(1..))]
+    heaps: Option<Vec<u8>>,
+    /// How many pieces can be taken at once in the subtraction game
+    #[arg(long, global = true, value_delimiter = ',', default_value = "1,2,3", value_parser = clap::value_parser!(u8).range(1..))]
+    subtraction_set: Vec<u8>,
+    /// Plays the misère variant of Tic-Tac-Toe (and wild Tic-Tac-Toe), Nim or the subtraction game,
+    /// where the player that lines up three pieces or takes the last piece loses
+    #[arg(long, global = true)]
+    misere: bool,
+    /// The seed of the random number generators, a random one when not given
+    #[arg(long, global = true)]
+    seed: Option<u64>,
+    #[command(flatten)]
+    player_options: PlayerOptions,
+}
+
+/// The parameters of the agents, they are shared by both players.
+#[derive(Args)]
+struct PlayerOptions {
+    /// How many moves ahead the alpha-beta and expectiminimax agents search
+    #[arg(long, global = true, default_value_t = 4)]
+    depth: u32,
+    /// How many iterations the MCTS agent runs for each move, or the CFR agent runs to learn
+    #[arg(long, global = true, default_value_t = 1000, value_parser = clap::value_parser!(u32).range(1..))]
+    iterations: u32,
+    /// The exploration constant of the MCTS agent
+    #[arg(long, global = true, default_value_t = std::f64::consts::SQRT_2)]
+    exploration: f64,
+    /// How many games the Q-learning agent plays against itself while learning
+    #[arg(long, global = true, default_value_t = 100_000)]
+    episodes: u32,
+    /// The learning rate of the Q-learning agent
+    #[arg(long, global = true, default_value_t = 0.5)]
+    learning_rate: f64,
+    /// The discount of future rewards of the Q-learning agent
+    #[arg(long, global = true, default_value_t = 0.9)]
+    discount: f64,
+    /// The exploration probability of the Q-learning agent in the first episode
+    #[arg(long, global = true, default_value_t = 1.0)]
+    epsilon_start: f64,
+    /// The exploration probability of the Q-learning agent in the last episode
+    #[arg(long, global = true, default_value_t = 0.05)]
+    epsilon_end: f64,
+    /// Makes the min-max and Q-learning agents store the symmetric states (e.g., the rotations of
+    /// the Tic-Tac-Toe board) only once while learning
+    #[arg(long, global = true)]
+    symmetries: bool,
 }
 
 #[derive(Subcommand)]
@@ -24,66 +93,460 @@ enum Commands {
         player_1: PlayerKind,
         #[arg(value_enum)]
         player_2: PlayerKind,
+        // The maximum number of games when playing with `--sprt`
         game_count: u32,
+        /// Stops as soon as the SPRT decides whether player 1 is ELO0 or ELO1 points stronger
+        #[arg(long, value_name = "ELO0,ELO1,ALPHA,BETA")]
+        sprt: Option<Sprt>,
+        /// How the results are written
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+        /// The file where the results are written, instead of the standard output
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// The file where the moves of every game are written, see the `replay` command
+        #[arg(long)]
+        record: Option<PathBuf>,
+        /// What happens when a player tries an illegal move: it forfeits the game, it retries up to N
+        /// times in a row before forfeiting, or a random legal move is played instead
+        #[arg(long, value_name = "forfeit|retry:<N>|substitute", default_value_t = IllegalMovePolicy::Forfeit)]
+        illegal_moves: IllegalMovePolicy,
     },
     Learn {
         player: PlayerKind,
     },
+    // Plays a round-robin tournament, where each pair of players plays `game_count` games
+    Tournament {
+        game_count: u32,
+        /// The kinds of the players, or the files of learned agents (e.g., a copy of
+        /// `qlearning-tic-tac-toe.bin` learned with other parameters)
+        #[arg(num_args = 2.., required = true, value_parser = TournamentPlayer::parse)]
+        players: Vec<TournamentPlayer>,
+        /// What happens when a player tries an illegal move: it forfeits the game, it retries up to N
+        /// times in a row before forfeiting, or a random legal move is played instead
+        #[arg(long, value_name = "forfeit|retry:<N>|substitute", default_value_t = IllegalMovePolicy::Forfeit)]
+        illegal_moves: IllegalMovePolicy,
+    },
+    // Shows the min-max value of every move in the board, e.g. `XO-|-X-|--O`
+    Analyze {
+        #[arg(value_name = "BOARD")]
+        position: String,
+    },
+    // Shows every state of a game recorded with `play --record`, the first one when no index is given
+    Replay {
+        file: PathBuf,
+        index: Option<u32>,
+    },
+    // Plays against an agent one game at a time, until the human stops
+    PlayHuman {
+        #[arg(value_enum)]
+        opponent: PlayerKind,
+    },
+    // Measures the nodes per second of a full Tic-Tac-Toe search, whatever the `--game` is
+    Bench,
+}
+#[derive(Clone, ValueEnum)]
+enum GameKind {
+    TicTacToe,
+    /// Tic-Tac-Toe where each player places either piece
+    WildTicTacToe,
+    ConnectFour,
+    Mnk,
+    UltimateTicTacToe,
+    Othello,
+    /// Othello in a 6x6 board
+    #[value(name = "othello-6x6")]
+    Othello6x6,
+    Nim,
+    /// Nim where the number of pieces taken at once must be in the `--subtraction-set`
+    SubtractionGame,
+    /// A race of two pieces for each player, moved by the roll of a die
+    DiceRace,
+    /// Poker with three cards, where each player sees only its own card
+    KuhnPoker,
+}
+
+/// A player of a tournament, one of the `PlayerKind`s or a learned agent stored in the `file`. The
+/// kind of the agent is told by the prefix of the file name, as in the files written by `learn`.
+#[derive(Clone)]
+struct TournamentPlayer {
+    kind: PlayerKind,
+    file: Option<PathBuf>,
 }
+
 #[derive(Clone, ValueEnum)]
 enum PlayerKind {
     Human,
     Random,
     MinMax,
+    AlphaBeta,
+    Expectiminimax,
+    Mcts,
+    QLearning,
+    SpragueGrundy,
+    Cfr,
 }
 
 fn main() -> Result<(), ReLearnError> {
     let args = Arguments::parse();
 
-    match args.command {
+    let options = &args.player_options;
+    let seed = args.seed.unwrap_or_else(|| fastrand::u64(..));
+
+    match args.game {
+        GameKind::TicTacToe => run(&TicTacToe::new(args.misere), args.command, options, seed),
+        GameKind::WildTicTacToe => run(
+            &WildTicTacToe::new(args.misere),
+            args.command,
+            options,
+            seed,
+        ),
+        GameKind::ConnectFour => run(&ConnectFour, args.command, options, seed),
+        GameKind::Mnk => run(
+            &MnkGame::new(args.board, args.k).map_err(ReLearnError::InvalidGame)?,
+            args.command,
+            options,
+            seed,
+        ),
+        GameKind::UltimateTicTacToe => run(&UltimateTicTacToe, args.command, options, seed),
+        GameKind::Othello => run(&Othello::new(8), args.command, options, seed),
+        GameKind::Othello6x6 => run(&Othello::new(6), args.command, options, seed),
+        GameKind::DiceRace => run(&DiceRace, args.command, options, seed),
+        GameKind::KuhnPoker => run(&KuhnPoker, args.command, options, seed),
+        GameKind::Nim => run(
+            &Nim::new(args.heaps.unwrap_or(vec![3, 4, 5]), None, args.misere),
+            args.command,
+            options,
+            seed,
+        ),
+        GameKind::SubtractionGame => run(
+            &Nim::new(
+                args.heaps.unwrap_or(vec![21]),
+                Some(args.subtraction_set),
+                args.misere,
+            ),
+            args.command,
+            options,
+            seed,
+        ),
+    }
+}
+
+fn run<G: Game>(
+    game: &G,
+    command: Commands,
+    options: &PlayerOptions,
+    seed: u64,
+) -> Result<(), ReLearnError> {
+    match command {
         Commands::Play {
             player_1,
             player_2,
             game_count,
+            sprt,
+            format,
+            output,
+            record,
+            illegal_moves,
         } => {
-            let player_1 = player_1.load_player()?;
-            let player_2 = player_2.load_player()?;
+            PlayerKind::check_batch(&[player_1.clone(), player_2.clone()])?;
+
+            let (name_1, name_2) = (player_1.name(), player_2.name());
+            let player_1 = player_1.load_player(game, options)?;
+            let player_2 = player_2.load_player(game, options)?;
+            let descriptions = [
+                PlayerDescription::new(name_1, player_1.as_ref()),
+                PlayerDescription::new(name_2, player_2.as_ref()),
+            ];
 
-            commands::play(player_1.as_ref(), player_2.as_ref(), game_count);
+            commands::play(
+                game,
+                player_1.as_ref(),
+                player_2.as_ref(),
+                &descriptions,
+                &MatchOptions {
+                    game_count,
+                    sprt,
+                    format,
+                    output,
+                    record,
+                    seed,
+                    illegal_moves,
+                },
+            )?;
         }
         Commands::Learn { player } => {
-            let mut player = player.create_player();
-            player.learn(&GAME);
-            player.save()?;
+            player.check_game(game)?;
+            let mut player = player.create_player(options);
+            println!("Seed: {seed}");
+            player.learn(game, &mut Rng::with_seed(seed));
+            player.save(game)?;
         }
+        Commands::Tournament {
+            game_count,
+            players,
+            illegal_moves,
+        } => {
+            let kinds: Vec<_> = players.iter().map(|player| player.kind.clone()).collect();
+            PlayerKind::check_batch(&kinds)?;
+
+            let players = players
+                .iter()
+                .map(|player| Ok((player.name(), player.load_player(game, options)?)))
+                .collect::<Result<Vec<_>, ReLearnError>>()?;
+
+            commands::tournament(game, &players, game_count, seed, illegal_moves);
+        }
+        Commands::Analyze { position } => {
+            let state = game
+                .parse_state(&position)
+                .map_err(ReLearnError::InvalidBoard)?;
+
+            commands::analyze(game, &state);
+        }
+        Commands::Replay { file, index } => commands::replay(game, &file, index)?,
+        Commands::PlayHuman { opponent } => {
+            let human = PlayerKind::Human.load_player(game, options)?;
+            let opponent_name = opponent.name();
+            let opponent = opponent.load_player(game, options)?;
+
+            commands::play_human(
+                game,
+                human.as_ref(),
+                opponent.as_ref(),
+                &opponent_name,
+                seed,
+            );
+        }
+        Commands::Bench => commands::bench(),
     };
 
     Ok(())
 }
 
 impl PlayerKind {
-    fn load_player(&self) -> Result<Box<dyn players::Player>, ReLearnError> {
+    fn load_player<G: Game>(
+        &self,
+        game: &G,
+        options: &PlayerOptions,
+    ) -> Result<Box<dyn Player<G>>, ReLearnError> {
+        self.check_game(game)?;
+
         match self {
-            PlayerKind::Human | PlayerKind::Random => Ok(self.create_player()),
-            PlayerKind::MinMax => {
-                let Ok(file) = File::open(minmax::FILE) else {
-                    return Err(ReLearnError::LoadAgentError(format!("Failed to load selected agent, did you run 'cargo run -r -- learn min-max' first?")));
-                };
+            PlayerKind::Random
+            | PlayerKind::AlphaBeta
+            | PlayerKind::Expectiminimax
+            | PlayerKind::Mcts
+            | PlayerKind::SpragueGrundy => Ok(self.create_player(options)),
+            PlayerKind::Human => {
+                // The hints are optional, so the human can play before the min-max agent learns
+                let hints = self.load_agent(Path::new(&minmax::file(game)), game).ok();
+                Ok(Box::new(HumanPlayer::new(hints)))
+            }
+            PlayerKind::MinMax => self.load_learned_agent(game, Path::new(&minmax::file(game))),
+            PlayerKind::QLearning => {
+                self.load_learned_agent(game, Path::new(&qlearning::file(game)))
+            }
+            PlayerKind::Cfr => self.load_learned_agent(game, Path::new(&cfr::file(game))),
+        }
+    }
 
-                let mut deserializer = rmp_serde::Deserializer::new(file);
-                let player: MinMaxPlayer = serde::Deserialize::deserialize(&mut deserializer)
-                    .map_err(|err| ReLearnError::LoadAgentError(err.to_string()))?;
+    /// Loads the learned agent of this kind stored in the `file`.
+    fn load_learned_agent<G: Game>(
+        &self,
+        game: &G,
+        file: &Path,
+    ) -> Result<Box<dyn Player<G>>, ReLearnError> {
+        self.check_game(game)?;
 
+        match self {
+            PlayerKind::MinMax => {
+                let player: MinMaxPlayer<G> = self.load_agent(file, game)?;
+                Ok(Box::new(player))
+            }
+            PlayerKind::QLearning => {
+                let player: QLearningPlayer<G> = self.load_agent(file, game)?;
+                Ok(Box::new(player))
+            }
+            PlayerKind::Cfr => {
+                let player: CfrPlayer<G> = self.load_agent(file, game)?;
                 Ok(Box::new(player))
             }
+            _ => Err(ReLearnError::InvalidPlayer(format!(
+                "The {} agent doesn't learn, so it has no file",
+                self.name()
+            ))),
+        }
+    }
+
+    fn load_agent<G: Game, T: DeserializeOwned>(
+        &self,
+        file: &Path,
+        game: &G,
+    ) -> Result<T, ReLearnError> {
+        let Ok(file) = File::open(file) else {
+            return Err(ReLearnError::LoadAgentError(format!(
+                "Failed to load the agent '{}', did you run 'cargo run -r -- learn {} --game {}' first?",
+                file.display(),
+                self.name(),
+                game.name()
+            )));
+        };
+
+        let mut deserializer = rmp_serde::Deserializer::new(file);
+        T::deserialize(&mut deserializer)
+            .map_err(|err| ReLearnError::LoadAgentError(err.to_string()))
+    }
+
+    /// Some agents only understand some kinds of games.
+    fn check_game<G: Game>(&self, game: &G) -> Result<(), ReLearnError> {
+        match self {
+            PlayerKind::MinMax
+            | PlayerKind::AlphaBeta
+            | PlayerKind::Expectiminimax
+            | PlayerKind::Mcts
+            | PlayerKind::QLearning
+                if game.hidden_information() =>
+            {
+                Err(ReLearnError::InvalidPlayer(format!(
+                    "The {} agent can't play the game '{}', which has hidden information, try the \
+                     cfr agent",
+                    self.name(),
+                    game.name()
+                )))
+            }
+            PlayerKind::Cfr if !game.hidden_information() => {
+                Err(ReLearnError::InvalidPlayer(format!(
+                    "The game '{}' has no hidden information, the cfr agent only plays games like \
+                     poker",
+                    game.name()
+                )))
+            }
+            PlayerKind::MinMax | PlayerKind::AlphaBeta | PlayerKind::Mcts if game.stochastic() => {
+                Err(ReLearnError::InvalidPlayer(format!(
+                    "The {} agent can't play the game '{}', which has chance events, try the \
+                     expectiminimax agent",
+                    self.name(),
+                    game.name()
+                )))
+            }
+            PlayerKind::SpragueGrundy if game.grundy_number(&game.initial_state()).is_none() => {
+                Err(ReLearnError::InvalidPlayer(format!(
+                    "The game '{}' has no Sprague-Grundy numbers",
+                    game.name()
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The matches are played in many threads, which can't all ask the human for moves.
+    fn check_batch(players: &[PlayerKind]) -> Result<(), ReLearnError> {
+        if players
+            .iter()
+            .any(|player| matches!(player, PlayerKind::Human))
+        {
+            return Err(ReLearnError::InvalidPlayer(
+                "The human can't play in matches, use the 'play-human' command".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn name(&self) -> String {
+        self.to_possible_value()
+            .expect("None of the variants are skipped")
+            .get_name()
+            .to_string()
+    }
+
+    /// How the files of the learned agents of this kind start, see e.g. `minmax::file`.
+    fn file_prefix(&self) -> Option<&'static str> {
+        match self {
+            PlayerKind::MinMax => Some("minmax-"),
+            PlayerKind::QLearning => Some("qlearning-"),
+            PlayerKind::Cfr => Some("cfr-"),
+            PlayerKind::Human
+            | PlayerKind::Random
+            | PlayerKind::AlphaBeta
+            | PlayerKind::Expectiminimax
+            | PlayerKind::Mcts
+            | PlayerKind::SpragueGrundy => None,
         }
     }
 
-    fn create_player(&self) -> Box<dyn players::Player + Sync + Send> {
+    fn create_player<G: Game>(&self, options: &PlayerOptions) -> Box<dyn Player<G>> {
         match self {
-            PlayerKind::Human => Box::new(HumanPlayer {}),
+            PlayerKind::Human => Box::new(HumanPlayer::<G>::new(None)),
             PlayerKind::Random => Box::new(RandomPlayer {}),
-            PlayerKind::MinMax => Box::new(MinMaxPlayer::new()),
+            PlayerKind::MinMax => Box::new(MinMaxPlayer::<G>::new(options.symmetries)),
+            PlayerKind::AlphaBeta => Box::new(AlphaBetaPlayer::new(options.depth, G::heuristic)),
+            PlayerKind::Expectiminimax => {
+                Box::new(ExpectiminimaxPlayer::new(options.depth, G::heuristic))
+            }
+            PlayerKind::Mcts => Box::new(MctsPlayer::new(options.iterations, options.exploration)),
+            PlayerKind::QLearning => Box::new(QLearningPlayer::<G>::new(QLearningParameters {
+                episodes: options.episodes,
+                learning_rate: options.learning_rate,
+                discount: options.discount,
+                epsilon_start: options.epsilon_start,
+                epsilon_end: options.epsilon_end,
+                symmetric: options.symmetries,
+            })),
+            PlayerKind::SpragueGrundy => Box::new(SpragueGrundyPlayer),
+            PlayerKind::Cfr => Box::new(CfrPlayer::<G>::new(options.iterations)),
+        }
+    }
+}
+
+impl TournamentPlayer {
+    /// Parses the name of a `PlayerKind` or the file of a learned agent.
+    fn parse(player: &str) -> Result<Self, String> {
+        if let Ok(kind) = PlayerKind::from_str(player, true) {
+            return Ok(TournamentPlayer { kind, file: None });
+        }
+
+        let file = PathBuf::from(player);
+        let file_name = file
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        PlayerKind::value_variants()
+            .iter()
+            .find(|kind| {
+                kind.file_prefix()
+                    .is_some_and(|prefix| file_name.starts_with(prefix))
+            })
+            .map(|kind| TournamentPlayer {
+                kind: kind.clone(),
+                file: Some(file.clone()),
+            })
+            .ok_or_else(|| {
+                format!(
+                    "Invalid player '{player}', expected a kind of player or the file of a \
+                     learned agent starting with 'minmax-', 'qlearning-' or 'cfr-'"
+                )
+            })
+    }
+
+    /// The file of the agent, or the name of its kind.
+    fn name(&self) -> String {
+        match &self.file {
+            Some(file) => file.display().to_string(),
+            None => self.kind.name(),
+        }
+    }
+
+    fn load_player<G: Game>(
+        &self,
+        game: &G,
+        options: &PlayerOptions,
+    ) -> Result<Box<dyn Player<G>>, ReLearnError> {
+        match &self.file {
+            Some(file) => self.kind.load_learned_agent(game, file),
+            None => self.kind.load_player(game, options),
         }
     }
 }
@@ -92,6 +555,11 @@ impl PlayerKind {
 pub enum ReLearnError {
     SaveAgentError(String),
     LoadAgentError(String),
+    InvalidBoard(String),
+    OutputError(String),
+    InvalidRecord(String),
+    InvalidPlayer(String),
+    InvalidGame(String),
 }
 
 impl fmt::Display for ReLearnError {
@@ -103,6 +571,21 @@ impl fmt::Display for ReLearnError {
             ReLearnError::LoadAgentError(error_msg) => {
                 write!(f, "Could not load the agent. Err: {error_msg}")
             }
+            ReLearnError::InvalidBoard(error_msg) => {
+                write!(f, "Could not parse the board. Err: {error_msg}")
+            }
+            ReLearnError::OutputError(error_msg) => {
+                write!(f, "Could not write the results. Err: {error_msg}")
+            }
+            ReLearnError::InvalidRecord(error_msg) => {
+                write!(f, "Could not replay the game. Err: {error_msg}")
+            }
+            ReLearnError::InvalidPlayer(error_msg) => {
+                write!(f, "Invalid player. Err: {error_msg}")
+            }
+            ReLearnError::InvalidGame(error_msg) => {
+                write!(f, "Invalid game. Err: {error_msg}")
+            }
         }
     }
 }
@@ -0,0 +1,309 @@
+use fastrand::Rng;
+
+use crate::{
+    game::{self, Game},
+    ReLearnError,
+};
+
+use super::{search_move, Player};
+
+/// The utility of a victory, any evaluation of a non-terminal state must be smaller than it.
+pub(super) const WIN: i64 = 1_000_000;
+
+/// A function that estimates how good a non-terminal state is for the given player.
+pub type Evaluation<G> = fn(&G, &<G as Game>::State, game::Player) -> i64;
+
+/// The `Alpha-Beta` algorithm is the `Min-Max` algorithm with pruning.
+///
+/// While exploring the state space graph it keeps track of the best value that the player
+/// (`alpha`) and the opponent (`beta`) can already guarantee, any branch that can't do better than
+/// that is not explored.
+///
+/// Instead of learning ahead of time, it searches when playing, up to `depth` moves ahead. The
+/// states in the cutoff are valued by the `evaluate` function, so it can play games whose state
+/// space is too big to be fully explored.
+///
+/// NOTE: The victories are valued by how soon they happen, so the agent prefers faster wins and
+/// slower losses.
+pub struct AlphaBetaPlayer<G: Game> {
+    depth: u32,
+    evaluate: Evaluation<G>,
+}
+
+impl<G: Game> Player<G> for AlphaBetaPlayer<G> {
+    fn play(&self, game: &G, state: &G::State, player: game::Player, _: &mut Rng) -> G::Action {
+        let mut alpha = -WIN - 1 - self.depth as i64;
+        let beta = -alpha;
+        let mut best_move: Option<_> = None;
+        // The search makes and unmakes its moves in a single state, see `search_move`
+        let mut state = state.clone();
+
+        for action in game.available_moves(&state) {
+            let action_value = search_move(game, &mut state, player, action, |next_state| {
+                self.minimize(
+                    game,
+                    next_state,
+                    player,
+                    self.depth.saturating_sub(1),
+                    alpha,
+                    beta,
+                )
+            });
+
+            if best_move.is_none() || action_value > alpha {
+                alpha = action_value;
+                best_move = Some(action);
+            }
+        }
+
+        best_move.expect("The agent should only play in non-terminal states")
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, f64)> {
+        vec![("depth", self.depth as f64)]
+    }
+
+    fn learn(&mut self, _: &G, _: &mut Rng) {}
+
+    fn save(&self, _: &G) -> Result<(), ReLearnError> {
+        Ok(())
+    }
+}
+
+impl<G: Game> AlphaBetaPlayer<G> {
+    pub(crate) fn new(depth: u32, evaluate: Evaluation<G>) -> Self {
+        AlphaBetaPlayer { depth, evaluate }
+    }
+
+    fn maximize(
+        &self,
+        game: &G,
+        state: &mut G::State,
+        player: game::Player,
+        depth: u32,
+        mut alpha: i64,
+        beta: i64,
+    ) -> i64 {
+        if let game::Status::Finished(maybe_winner) = game.status(state) {
+            return utility(maybe_winner, player, depth);
+        }
+
+        if depth == 0 {
+            return (self.evaluate)(game, state, player);
+        }
+
+        let mut highest_value = i64::MIN;
+
+        for action in game.available_moves(state) {
+            let action_value = search_move(game, state, player, action, |next_state| {
+                self.minimize(game, next_state, player, depth - 1, alpha, beta)
+            });
+            highest_value = highest_value.max(action_value);
+
+            // The opponent already has a better option than this branch
+            if highest_value >= beta {
+                break;
+            }
+
+            alpha = alpha.max(highest_value);
+        }
+
+        highest_value
+    }
+
+    fn minimize(
+        &self,
+        game: &G,
+        state: &mut G::State,
+        player: game::Player,
+        depth: u32,
+        alpha: i64,
+        mut beta: i64,
+    ) -> i64 {
+        if let game::Status::Finished(maybe_winner) = game.status(state) {
+            return utility(maybe_winner, player, depth);
+        }
+
+        if depth == 0 {
+            return (self.evaluate)(game, state, player);
+        }
+
+        let mut lowest_value = i64::MAX;
+
+        for action in game.available_moves(state) {
+            let action_value =
+                search_move(game, state, player.next_player(), action, |next_state| {
+                    self.maximize(game, next_state, player, depth - 1, alpha, beta)
+                });
+            lowest_value = lowest_value.min(action_value);
+
+            // The player already has a better option than this branch
+            if lowest_value <= alpha {
+                break;
+            }
+
+            beta = beta.min(lowest_value);
+        }
+
+        lowest_value
+    }
+}
+
+/// The remaining `depth` is added to the victories (and losses), so the sooner it happens the bigger
+/// is its value.
+pub(super) fn utility(maybe_winner: Option<game::Player>, player: game::Player, depth: u32) -> i64 {
+    match maybe_winner {
+        Some(winner) => {
+            if winner == player {
+                WIN + depth as i64
+            } else {
+                -WIN - depth as i64
+            }
+        }
+        None => 0,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::{
+        game::{ConnectFour, TicTacToe},
+        players::{minmax::Outcome, MinMaxPlayer},
+    };
+
+    #[test]
+    fn test_solve() {
+        let game = TicTacToe::default();
+        let mut solver = MinMaxPlayer::new(false);
+        solver.learn(&game, &mut Rng::new());
+
+        let player = AlphaBetaPlayer::new(9, TicTacToe::heuristic);
+        let mut states = vec![game.initial_state()];
+        let mut visited = HashSet::new();
+
+        // Searching the whole game, it values every state as the min-max solution and plays one
+        // of its best moves
+        while let Some(state) = states.pop() {
+            if game.status(&state) != game::Status::OnGoing || !visited.insert(game.encode(&state))
+            {
+                continue;
+            }
+
+            let player_to_move = game.current_player(&state);
+            let solution = solver.solution(&game, &state).unwrap();
+            let value = player.maximize(
+                &game,
+                &mut state.clone(),
+                player_to_move,
+                9,
+                -2 * WIN,
+                2 * WIN,
+            );
+            let distance = solution.value.distance as i64;
+            let expected_value = match solution.value.outcome {
+                Outcome::Win => WIN + 9 - distance,
+                Outcome::Draw => 0,
+                Outcome::Loss => -WIN - 9 + distance,
+            };
+
+            assert_eq!(value, expected_value, "{state}");
+            assert!(solution.best_moves.contains(&player.play(
+                &game,
+                &state,
+                player_to_move,
+                &mut Rng::new()
+            )));
+
+            for action in game.available_moves(&state) {
+                let mut next_state = state;
+                game.act(player_to_move, action, &mut next_state).unwrap();
+                states.push(next_state);
+            }
+        }
+    }
+
+    /// The value of the `Min-Max` search up to the `depth`, without pruning.
+    fn min_max<G: Game>(
+        game: &G,
+        state: &G::State,
+        player: game::Player,
+        depth: u32,
+        evaluate: Evaluation<G>,
+    ) -> i64 {
+        if let game::Status::Finished(maybe_winner) = game.status(state) {
+            return utility(maybe_winner, player, depth);
+        }
+
+        if depth == 0 {
+            return evaluate(game, state, player);
+        }
+
+        let player_to_move = game.current_player(state);
+        let values = game.available_moves(state).into_iter().map(|action| {
+            let mut next_state = state.clone();
+            game.act(player_to_move, action, &mut next_state).unwrap();
+
+            min_max(game, &next_state, player, depth - 1, evaluate)
+        });
+
+        if player_to_move == player {
+            values.max().unwrap()
+        } else {
+            values.min().unwrap()
+        }
+    }
+
+    #[test]
+    fn test_pruning() {
+        let game = ConnectFour;
+        let depth = 4;
+        let player = AlphaBetaPlayer::new(depth, ConnectFour::heuristic);
+        let mut rng = Rng::with_seed(0);
+
+        // The pruned search chooses the same move as the full one, the first with the highest value
+        for _ in 0..20 {
+            let mut state = game.initial_state();
+
+            for _ in 0..rng.usize(..12) {
+                let available_moves = game.available_moves(&state);
+                let action = available_moves[rng.usize(..available_moves.len())];
+                game.act(game.current_player(&state), action, &mut state)
+                    .unwrap();
+
+                if game.status(&state) != game::Status::OnGoing {
+                    state = game.initial_state();
+                }
+            }
+
+            let player_to_move = game.current_player(&state);
+            let mut best_move = None;
+            let mut highest_value = i64::MIN;
+
+            for action in game.available_moves(&state) {
+                let mut next_state = state.clone();
+                game.act(player_to_move, action, &mut next_state).unwrap();
+
+                let value = min_max(
+                    &game,
+                    &next_state,
+                    player_to_move,
+                    depth - 1,
+                    ConnectFour::heuristic,
+                );
+                if value > highest_value {
+                    highest_value = value;
+                    best_move = Some(action);
+                }
+            }
+
+            assert_eq!(
+                Some(player.play(&game, &state, player_to_move, &mut rng)),
+                best_move,
+                "{state}"
+            );
+        }
+    }
+}
@@ -0,0 +1,434 @@
+use std::{collections::HashMap, fs::File};
+
+use fastrand::Rng;
+use rmp_serde::Serializer;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game::{self, Game, Status},
+    ReLearnError,
+};
+
+use super::Player;
+
+/// How many times the exploitability is shown while learning.
+const REPORTS: u32 = 10;
+
+/// The file where the learned agent is stored, one for each game.
+pub fn file<G: Game>(game: &G) -> String {
+    format!("cfr-{}.bin", game.name())
+}
+
+/// The `Counterfactual Regret Minimization` algorithm, for two-player, zero-sum games with hidden
+/// information (e.g., poker).
+///
+/// The player doesn't know the state, only its information set (see `Game::information_set`), so it
+/// plays a strategy: a probability for each action in each information set. While learning, it
+/// traverses the whole game tree many times, and in each information set it adds up the regret of
+/// not having played each action (how much more it would have won, weighted by the probability of
+/// the other player and the chance events reaching the state). The next strategy plays the actions
+/// in proportion to their positive regrets (regret matching).
+///
+/// NOTE: The strategies themselves don't converge, but their average does, to a Nash equilibrium.
+/// So the agent plays the average strategy, which can't be exploited (see `exploitability`).
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct CfrPlayer<G: Game> {
+    /// Keyed by the encoded information set, see `Game::encode`.
+    information_sets: HashMap<u64, InformationSet<G>>,
+    /// How many times the game tree is traversed while learning.
+    #[serde(default)]
+    iterations: u32,
+}
+
+/// The sums are indexed as the available moves of the information set.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+struct InformationSet<G: Game> {
+    actions: Vec<G::Action>,
+    regret_sum: Vec<f64>,
+    /// The probabilities of the strategies, weighted by the probability of the player reaching the
+    /// information set.
+    strategy_sum: Vec<f64>,
+}
+
+impl<G: Game> Player<G> for CfrPlayer<G> {
+    fn play(&self, game: &G, state: &G::State, player: game::Player, rng: &mut Rng) -> G::Action {
+        let strategy = self.average_strategy(game, state, player);
+        let available_moves = game.available_moves(state);
+
+        // The probabilities may not add up to 1 exactly, the last action takes what is left
+        let mut draw = rng.f64();
+        for (&action, probability) in available_moves.iter().zip(strategy) {
+            if draw < probability {
+                return action;
+            }
+
+            draw -= probability;
+        }
+
+        *available_moves
+            .last()
+            .expect("The agent should only play in non-terminal states")
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, f64)> {
+        vec![("iterations", self.iterations as f64)]
+    }
+
+    fn learn(&mut self, game: &G, _: &mut Rng) {
+        let report_every = (self.iterations / REPORTS).max(1);
+
+        for iteration in 1..=self.iterations {
+            self.cfr(game, &game.initial_state(), [1.0; 2], 1.0);
+
+            if iteration % report_every == 0 || iteration == self.iterations {
+                println!(
+                    "Iteration {iteration}: exploitability {:.6}",
+                    self.exploitability(game)
+                );
+            }
+        }
+    }
+
+    fn save(&self, game: &G) -> Result<(), ReLearnError> {
+        let mut file = File::create(file(game))
+            .map_err(|err| ReLearnError::SaveAgentError(err.to_string()))?;
+
+        self.serialize(&mut Serializer::new(&mut file))
+            .map_err(|err| ReLearnError::SaveAgentError(err.to_string()))
+    }
+}
+
+impl<G: Game> CfrPlayer<G> {
+    pub(crate) fn new(iterations: u32) -> Self {
+        CfrPlayer {
+            information_sets: HashMap::new(),
+            iterations,
+        }
+    }
+
+    /// How much a best response to the average strategy wins, averaged over both seats. It's never
+    /// negative and it's 0 only in a Nash equilibrium, so it tells how far the agent is from it.
+    pub(crate) fn exploitability(&self, game: &G) -> f64 {
+        let best_responses: f64 = [game::Player::X, game::Player::O]
+            .into_iter()
+            .map(|player| BestResponse::new(self, game, player).value(&game.initial_state()))
+            .sum();
+
+        best_responses / 2.0
+    }
+
+    /// Updates the regrets and the strategies of every information set reachable from the state,
+    /// returning the expected utility of each player with the current strategies.
+    ///
+    /// The `reach` is the probability of each player playing the moves to the state, and the
+    /// `chance_reach` the probability of the chance events.
+    fn cfr(&mut self, game: &G, state: &G::State, reach: [f64; 2], chance_reach: f64) -> [f64; 2] {
+        if game.status(state) != Status::OnGoing {
+            return [game::Player::X, game::Player::O].map(|player| game.utility(state, player));
+        }
+
+        let player = game.current_player(state);
+        let chance_events = game.chance_events(state);
+
+        if !chance_events.is_empty() {
+            let mut utilities = [0.0; 2];
+
+            for (event, probability) in chance_events {
+                let next_state = Self::next_state(game, state, player, event);
+                let event_utilities =
+                    self.cfr(game, &next_state, reach, chance_reach * probability);
+
+                for (utility, event_utility) in utilities.iter_mut().zip(event_utilities) {
+                    *utility += probability * event_utility;
+                }
+            }
+
+            return utilities;
+        }
+
+        let key = game.encode(&game.information_set(state, player));
+        let strategy = self
+            .information_sets
+            .entry(key)
+            .or_insert_with(|| InformationSet::new(game.available_moves(state)))
+            .strategy();
+        let actions = self.information_sets[&key].actions.clone();
+
+        let mut utilities = [0.0; 2];
+        let mut action_utilities = Vec::with_capacity(actions.len());
+
+        for (&action, &probability) in actions.iter().zip(&strategy) {
+            let mut next_reach = reach;
+            next_reach[player as usize] *= probability;
+
+            let next_state = Self::next_state(game, state, player, action);
+            let action_utility = self.cfr(game, &next_state, next_reach, chance_reach);
+
+            for (utility, action_utility) in utilities.iter_mut().zip(action_utility) {
+                *utility += probability * action_utility;
+            }
+            action_utilities.push(action_utility[player as usize]);
+        }
+
+        let opponent_reach = reach[player.next_player() as usize] * chance_reach;
+        // SAFETY: The information set was inserted above
+        let information_set = unsafe { self.information_sets.get_mut(&key).unwrap_unchecked() };
+
+        for (index, action_utility) in action_utilities.into_iter().enumerate() {
+            information_set.regret_sum[index] +=
+                opponent_reach * (action_utility - utilities[player as usize]);
+            information_set.strategy_sum[index] += reach[player as usize] * strategy[index];
+        }
+
+        utilities
+    }
+
+    /// The probability of each available move in the average strategy, uniform in the information
+    /// sets not reached while learning.
+    fn average_strategy(&self, game: &G, state: &G::State, player: game::Player) -> Vec<f64> {
+        let key = game.encode(&game.information_set(state, player));
+
+        match self.information_sets.get(&key) {
+            Some(information_set) => normalize(&information_set.strategy_sum),
+            None => normalize(&vec![1.0; game.available_moves(state).len()]),
+        }
+    }
+
+    fn next_state(game: &G, state: &G::State, player: game::Player, action: G::Action) -> G::State {
+        let mut next_state = state.clone();
+
+        // SAFETY: we draw the actions from the `available_moves` and `chance_events` methods
+        unsafe { game.act(player, action, &mut next_state).unwrap_unchecked() };
+
+        next_state
+    }
+}
+
+impl<G: Game> InformationSet<G> {
+    fn new(actions: Vec<G::Action>) -> Self {
+        InformationSet {
+            regret_sum: vec![0.0; actions.len()],
+            strategy_sum: vec![0.0; actions.len()],
+            actions,
+        }
+    }
+
+    /// Regret matching: the actions are played in proportion to their positive regrets.
+    fn strategy(&self) -> Vec<f64> {
+        let positive_regrets: Vec<_> = self
+            .regret_sum
+            .iter()
+            .map(|&regret| regret.max(0.0))
+            .collect();
+
+        normalize(&positive_regrets)
+    }
+}
+
+/// Scales the weights to add up to 1, or gives the same probability to each one when they are all
+/// 0.
+fn normalize(weights: &[f64]) -> Vec<f64> {
+    let sum: f64 = weights.iter().sum();
+
+    if sum > 0.0 {
+        weights.iter().map(|&weight| weight / sum).collect()
+    } else {
+        vec![1.0 / weights.len() as f64; weights.len()]
+    }
+}
+
+/// The best response of the `player` to the average strategy of the agent, the strategy that wins
+/// the most against it knowing how it plays (but not its hidden information).
+struct BestResponse<'a, G: Game> {
+    agent: &'a CfrPlayer<G>,
+    game: &'a G,
+    player: game::Player,
+    /// The states in each information set of the player, with the probability of the other player
+    /// and the chance events reaching them.
+    information_sets: HashMap<u64, Vec<(G::State, f64)>>,
+    /// The best action in each information set, as they are found.
+    best_actions: HashMap<u64, G::Action>,
+}
+
+impl<'a, G: Game> BestResponse<'a, G> {
+    fn new(agent: &'a CfrPlayer<G>, game: &'a G, player: game::Player) -> Self {
+        let mut best_response = BestResponse {
+            agent,
+            game,
+            player,
+            information_sets: HashMap::new(),
+            best_actions: HashMap::new(),
+        };
+        best_response.collect(&game.initial_state(), 1.0);
+
+        best_response
+    }
+
+    /// Stores every state of the player reachable from the state in its information set.
+    fn collect(&mut self, state: &G::State, reach: f64) {
+        if self.game.status(state) != Status::OnGoing {
+            return;
+        }
+
+        let player = self.game.current_player(state);
+
+        for (action, probability) in self.outcomes(state) {
+            let next_state = CfrPlayer::next_state(self.game, state, player, action);
+
+            match probability {
+                Some(probability) => self.collect(&next_state, reach * probability),
+                None => self.collect(&next_state, reach),
+            }
+        }
+
+        if self.game.chance_events(state).is_empty() && player == self.player {
+            let key = self.game.encode(&self.game.information_set(state, player));
+            self.information_sets
+                .entry(key)
+                .or_default()
+                .push((state.clone(), reach));
+        }
+    }
+
+    /// The expected utility of the player in the state, playing the best response.
+    fn value(&mut self, state: &G::State) -> f64 {
+        if self.game.status(state) != Status::OnGoing {
+            return self.game.utility(state, self.player);
+        }
+
+        let player = self.game.current_player(state);
+
+        if self.game.chance_events(state).is_empty() && player == self.player {
+            let action = self.best_action(state);
+            let next_state = CfrPlayer::next_state(self.game, state, player, action);
+
+            return self.value(&next_state);
+        }
+
+        self.outcomes(state)
+            .into_iter()
+            .map(|(action, probability)| {
+                let next_state = CfrPlayer::next_state(self.game, state, player, action);
+
+                // SAFETY: Only the player's own actions have no probability
+                unsafe { probability.unwrap_unchecked() * self.value(&next_state) }
+            })
+            .sum()
+    }
+
+    /// The action with the highest expected utility over all the states of the information set.
+    fn best_action(&mut self, state: &G::State) -> G::Action {
+        let key = self
+            .game
+            .encode(&self.game.information_set(state, self.player));
+
+        if let Some(&action) = self.best_actions.get(&key) {
+            return action;
+        }
+
+        let states = self.information_sets[&key].clone();
+        let action_value = |best_response: &mut Self, action: G::Action| -> f64 {
+            states
+                .iter()
+                .map(|(state, reach)| {
+                    let next_state = CfrPlayer::next_state(
+                        best_response.game,
+                        state,
+                        best_response.player,
+                        action,
+                    );
+
+                    reach * best_response.value(&next_state)
+                })
+                .sum()
+        };
+
+        let mut best_action = None;
+        let mut highest_value = f64::NEG_INFINITY;
+
+        for action in self.game.available_moves(state) {
+            let value = action_value(self, action);
+
+            if value > highest_value {
+                highest_value = value;
+                best_action = Some(action);
+            }
+        }
+
+        // SAFETY: The non-terminal states without chance events have available moves
+        let best_action = unsafe { best_action.unwrap_unchecked() };
+        self.best_actions.insert(key, best_action);
+
+        best_action
+    }
+
+    /// The chance events and the actions of the agent with their probabilities, or the actions of
+    /// the player without them.
+    fn outcomes(&self, state: &G::State) -> Vec<(G::Action, Option<f64>)> {
+        let chance_events = self.game.chance_events(state);
+
+        if !chance_events.is_empty() {
+            return chance_events
+                .into_iter()
+                .map(|(event, probability)| (event, Some(probability)))
+                .collect();
+        }
+
+        let player = self.game.current_player(state);
+        let available_moves = self.game.available_moves(state);
+
+        if player == self.player {
+            available_moves
+                .into_iter()
+                .map(|action| (action, None))
+                .collect()
+        } else {
+            let strategy = self.agent.average_strategy(self.game, state, player);
+
+            available_moves
+                .into_iter()
+                .zip(strategy)
+                .map(|(action, probability)| (action, Some(probability)))
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::KuhnPoker;
+
+    #[test]
+    fn test_kuhn_poker() {
+        let mut agent = CfrPlayer::new(0);
+
+        // The uniform strategy is easily exploited
+        let initial_exploitability = agent.exploitability(&KuhnPoker);
+        assert!(initial_exploitability > 0.1, "{initial_exploitability}");
+
+        for _ in 0..2000 {
+            agent.cfr(&KuhnPoker, &KuhnPoker.initial_state(), [1.0; 2], 1.0);
+        }
+
+        let exploitability = agent.exploitability(&KuhnPoker);
+        assert!(exploitability < 0.01, "{exploitability}");
+
+        // The value of the game is -1/18 for the first player
+        let value = agent.cfr(&KuhnPoker, &KuhnPoker.initial_state(), [1.0; 2], 1.0);
+        assert!((value[0] + 1.0 / 18.0).abs() < 0.02, "{value:?}");
+
+        // Facing a bet, O always calls with the king and folds the jack
+        let mut state = KuhnPoker.initial_state();
+        for action in ["deal-QK", "bet"] {
+            let player = KuhnPoker.current_player(&state);
+            KuhnPoker
+                .act(player, action.parse().unwrap(), &mut state)
+                .unwrap();
+        }
+        let strategy = agent.average_strategy(&KuhnPoker, &state, game::Player::O);
+        assert!(strategy[0] > 0.99, "{strategy:?}");
+    }
+}
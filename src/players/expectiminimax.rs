@@ -0,0 +1,233 @@
+use fastrand::Rng;
+
+use crate::{
+    game::{self, Game},
+    ReLearnError,
+};
+
+use super::{
+    alphabeta::{utility, Evaluation, WIN},
+    Player,
+};
+
+/// The `Expectiminimax` algorithm is the `Min-Max` algorithm for games with chance events (e.g., the
+/// roll of a die).
+///
+/// Besides the maximization (the player's turn) and minimization (the opponent's turn) steps, it
+/// has an expectation step for the chance events, where the value of the state is the average
+/// value of the states after each event, weighted by its probability (see `Game::chance_events`).
+///
+/// Like the `Alpha-Beta` agent, it searches when playing up to `depth` moves ahead (the chance
+/// events don't count as moves) and the states in the cutoff are valued by the `evaluate`
+/// function. As the average depends on every event, the branches are not pruned. In the
+/// deterministic games it plays like the `Alpha-Beta` agent.
+pub struct ExpectiminimaxPlayer<G: Game> {
+    depth: u32,
+    evaluate: Evaluation<G>,
+}
+
+impl<G: Game> Player<G> for ExpectiminimaxPlayer<G> {
+    fn play(&self, game: &G, state: &G::State, player: game::Player, _: &mut Rng) -> G::Action {
+        let mut highest_value = -WIN - 1 - self.depth as i64;
+        let mut best_move: Option<_> = None;
+
+        for action in game.available_moves(state) {
+            let mut next_state = state.clone();
+
+            // SAFETY: we draw the actions from the `available_moves` method
+            unsafe { game.act(player, action, &mut next_state).unwrap_unchecked() };
+
+            let action_value =
+                self.minimize(game, &next_state, player, self.depth.saturating_sub(1));
+
+            if best_move.is_none() || action_value > highest_value {
+                highest_value = action_value;
+                best_move = Some(action);
+            }
+        }
+
+        best_move.expect("The agent should only play in non-terminal states")
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, f64)> {
+        vec![("depth", self.depth as f64)]
+    }
+
+    fn learn(&mut self, _: &G, _: &mut Rng) {}
+
+    fn save(&self, _: &G) -> Result<(), ReLearnError> {
+        Ok(())
+    }
+}
+
+/// One of the steps of the search, which is repeated after each chance event.
+type Step<G> = fn(&ExpectiminimaxPlayer<G>, &G, &<G as Game>::State, game::Player, u32) -> i64;
+
+impl<G: Game> ExpectiminimaxPlayer<G> {
+    pub(crate) fn new(depth: u32, evaluate: Evaluation<G>) -> Self {
+        ExpectiminimaxPlayer { depth, evaluate }
+    }
+
+    fn maximize(&self, game: &G, state: &G::State, player: game::Player, depth: u32) -> i64 {
+        if let game::Status::Finished(maybe_winner) = game.status(state) {
+            return utility(maybe_winner, player, depth);
+        }
+
+        let chance_events = game.chance_events(state);
+        if !chance_events.is_empty() {
+            return self.expect(game, state, player, depth, &chance_events, Self::maximize);
+        }
+
+        if depth == 0 {
+            return (self.evaluate)(game, state, player);
+        }
+
+        game.available_moves(state)
+            .into_iter()
+            .map(|action| {
+                let mut next_state = state.clone();
+
+                // SAFETY: we draw the actions from the `available_moves` method
+                unsafe { game.act(player, action, &mut next_state).unwrap_unchecked() };
+
+                self.minimize(game, &next_state, player, depth - 1)
+            })
+            .max()
+            .unwrap_or(i64::MIN)
+    }
+
+    fn minimize(&self, game: &G, state: &G::State, player: game::Player, depth: u32) -> i64 {
+        if let game::Status::Finished(maybe_winner) = game.status(state) {
+            return utility(maybe_winner, player, depth);
+        }
+
+        let chance_events = game.chance_events(state);
+        if !chance_events.is_empty() {
+            return self.expect(game, state, player, depth, &chance_events, Self::minimize);
+        }
+
+        if depth == 0 {
+            return (self.evaluate)(game, state, player);
+        }
+
+        game.available_moves(state)
+            .into_iter()
+            .map(|action| {
+                let mut next_state = state.clone();
+
+                // SAFETY: we draw the actions from the `available_moves` method
+                unsafe {
+                    game.act(player.next_player(), action, &mut next_state)
+                        .unwrap_unchecked()
+                };
+
+                self.maximize(game, &next_state, player, depth - 1)
+            })
+            .min()
+            .unwrap_or(i64::MAX)
+    }
+
+    /// The average value of the states after each chance event, which happen before the same
+    /// player moves, so the same `step` continues the search.
+    fn expect(
+        &self,
+        game: &G,
+        state: &G::State,
+        player: game::Player,
+        depth: u32,
+        chance_events: &[(G::Action, f64)],
+        step: Step<G>,
+    ) -> i64 {
+        let player_to_move = game.current_player(state);
+
+        let expected_value: f64 = chance_events
+            .iter()
+            .map(|&(event, probability)| {
+                let mut next_state = state.clone();
+
+                // SAFETY: we draw the events from the `chance_events` method
+                unsafe {
+                    game.act(player_to_move, event, &mut next_state)
+                        .unwrap_unchecked()
+                };
+
+                probability * step(self, game, &next_state, player, depth) as f64
+            })
+            .sum();
+
+        expected_value.round() as i64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        game::{DiceRace, IllegalMovePolicy},
+        players::RandomPlayer,
+    };
+
+    #[test]
+    fn test_hit() {
+        let game = DiceRace;
+        let mut state = game.initial_state();
+
+        for (player, action) in [
+            (game::Player::X, "roll-1"),
+            (game::Player::X, "1"),
+            (game::Player::O, "roll-5"),
+            (game::Player::O, "2"),
+            (game::Player::X, "roll-2"),
+            (game::Player::X, "2"),
+            (game::Player::O, "roll-4"),
+            (game::Player::O, "2"),
+            (game::Player::X, "roll-4"),
+            (game::Player::X, "2"),
+            (game::Player::O, "roll-4"),
+            (game::Player::O, "1"),
+            (game::Player::X, "roll-3"),
+        ] {
+            game.act(player, action.parse().unwrap(), &mut state)
+                .unwrap();
+        }
+        assert_eq!(
+            state.to_string(),
+            "X: piece 1 at 1, piece 2 at 6\nO: piece 1 at 4, piece 2 at 9\nX rolled 3\n"
+        );
+
+        // X can send either piece of O back to the start, the furthest one is worth more
+        for depth in 1..=3 {
+            let player = ExpectiminimaxPlayer::new(depth, DiceRace::heuristic);
+            let action = player.play(&game, &state, game::Player::X, &mut Rng::new());
+
+            assert_eq!(action.to_string(), "2");
+        }
+    }
+
+    #[test]
+    fn test_play() {
+        let game = DiceRace;
+        let player = ExpectiminimaxPlayer::new(2, DiceRace::heuristic);
+        let mut rng = Rng::with_seed(0);
+
+        // The race has a lot of luck, but the search still wins more games than the random agent
+        let victories = (0..200)
+            .filter(|&index| {
+                let (record, seat) = if index % 2 == 0 {
+                    let record =
+                        game.play(&player, &RandomPlayer, IllegalMovePolicy::Forfeit, &mut rng);
+                    (record, game::Player::X)
+                } else {
+                    let record =
+                        game.play(&RandomPlayer, &player, IllegalMovePolicy::Forfeit, &mut rng);
+                    (record, game::Player::O)
+                };
+
+                assert!(record.illegal_moves.is_empty());
+                record.winner == Some(seat)
+            })
+            .count();
+
+        assert!(victories > 110, "{victories}");
+    }
+}
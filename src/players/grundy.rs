@@ -0,0 +1,125 @@
+use fastrand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game::{self, Game},
+    ReLearnError,
+};
+
+use super::Player;
+
+/// Solves the impartial games by their Sprague-Grundy numbers (see `Game::grundy_number`) instead
+/// of searching the game tree, the states with number `0` are lost for the player to move. So it
+/// plays a move to such a state when there is one, and a random move otherwise.
+///
+/// As the number tells the outcome with perfect play, its results can be checked against the
+/// theory (e.g., it always wins the Nim games where the initial heaps have a non-zero nim-sum as
+/// the first player).
+#[derive(Serialize, Deserialize)]
+pub struct SpragueGrundyPlayer;
+
+impl<G: Game> Player<G> for SpragueGrundyPlayer {
+    fn play(&self, game: &G, state: &G::State, player: game::Player, rng: &mut Rng) -> G::Action {
+        let available_moves = game.available_moves(state);
+        let winning_moves: Vec<_> = available_moves
+            .iter()
+            .copied()
+            .filter(|&action| {
+                let mut next_state = state.clone();
+
+                // SAFETY: we draw the actions from the `available_moves` method
+                unsafe { game.act(player, action, &mut next_state).unwrap_unchecked() };
+
+                game.grundy_number(&next_state)
+                    .expect("The game should have Sprague-Grundy numbers")
+                    == 0
+            })
+            .collect();
+
+        // In a lost state any move will do
+        let moves = if winning_moves.is_empty() {
+            available_moves
+        } else {
+            winning_moves
+        };
+
+        moves[rng.usize(..moves.len())]
+    }
+
+    fn learn(&mut self, _: &G, _: &mut Rng) {}
+
+    fn save(&self, _: &G) -> Result<(), ReLearnError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        game::{IllegalMovePolicy, Nim, Status},
+        players::{minmax::Outcome, MinMaxPlayer, RandomPlayer},
+    };
+
+    #[test]
+    fn test_grundy_numbers() {
+        for (heaps, subtraction_set, misere) in [
+            (vec![2, 3, 4], None, false),
+            (vec![2, 3, 4], None, true),
+            (vec![5, 7], Some(vec![1, 3, 4]), false),
+        ] {
+            let game = Nim::new(heaps.clone(), subtraction_set, misere);
+            let mut min_max = MinMaxPlayer::new(false);
+            min_max.learn(&game, &mut Rng::new());
+
+            // The Sprague-Grundy numbers agree with the min-max values in every smaller state
+            let state_count: usize = heaps.iter().map(|&size| size as usize + 1).product();
+
+            for index in 0..state_count {
+                let mut remaining = index;
+                let board = heaps
+                    .iter()
+                    .map(|&size| {
+                        let heap = remaining % (size as usize + 1);
+                        remaining /= size as usize + 1;
+                        heap.to_string()
+                    })
+                    .collect::<Vec<_>>()
+                    .join("|");
+                let state = game.parse_state(&board).unwrap();
+
+                if game.status(&state) != Status::OnGoing {
+                    continue;
+                }
+
+                let outcome = min_max.solution(&game, &state).unwrap().value.outcome;
+                assert_eq!(
+                    game.grundy_number(&state) == Some(0),
+                    outcome == Outcome::Loss,
+                    "{board}"
+                );
+            }
+        }
+
+        // The misère subtraction games have no numbers
+        let game = Nim::new(vec![5], Some(vec![1, 2]), true);
+        assert!(game.grundy_number(&game.initial_state()).is_none());
+    }
+
+    #[test]
+    fn test_play() {
+        // The nim-sum of the heaps is 2, so the first player always wins
+        let game = Nim::new(vec![3, 4, 5], None, false);
+
+        for seed in 0..20 {
+            let record = game.play(
+                &SpragueGrundyPlayer,
+                &RandomPlayer,
+                IllegalMovePolicy::Forfeit,
+                &mut Rng::with_seed(seed),
+            );
+
+            assert_eq!(record.winner, Some(game::Player::X));
+        }
+    }
+}
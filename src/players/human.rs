@@ -1,33 +1,147 @@
-use serde::{Deserialize, Serialize};
+use fastrand::Rng;
 
-use super::Player;
+use super::{Decision, MinMaxPlayer, Player};
 use crate::{
-    game::{self, Game, State},
+    game::{self, Game},
     ReLearnError,
 };
-use std::io;
+use std::io::{self, Write};
 
-#[derive(Serialize, Deserialize)]
-pub struct HumanPlayer;
+const HELP: &str = "Commands:
+  <move>  Plays the move, e.g. the coordinates of the field (b2)
+  undo    Takes back your last move and the opponent's
+  hint    Shows the best moves, according to the min-max agent
+  resign  Gives the game up
+  help    Shows this message";
 
-impl Player for HumanPlayer {
-    fn play(&self, state: &State, _: game::Player) -> u8 {
-        let available_moves = Game::available_moves(state);
+/// A person playing in the console, the moves are written with their labels (see `Game::label`).
+/// With a learned min-max agent it can also show the best moves.
+pub struct HumanPlayer<G: Game> {
+    hints: Option<MinMaxPlayer<G>>,
+}
+
+impl<G: Game> Player<G> for HumanPlayer<G> {
+    fn play(&self, game: &G, state: &G::State, player: game::Player, _: &mut Rng) -> G::Action {
+        loop {
+            match self.prompt(game, state, player) {
+                Some(Decision::Play(action)) => return action,
+                Some(Decision::Undo | Decision::Resign) => println!("You can only play a move now"),
+                None => panic!("The standard input was closed"),
+            }
+        }
+    }
 
-        println!("{state}");
-        println!("Available moves: {available_moves:?}");
+    fn decide(
+        &self,
+        game: &G,
+        state: &G::State,
+        player: game::Player,
+        _: &mut Rng,
+    ) -> Decision<G::Action> {
+        // Nobody is there to play when the input is closed
+        self.prompt(game, state, player).unwrap_or(Decision::Resign)
+    }
 
-        let mut buf = String::new();
-        io::stdin().read_line(&mut buf).unwrap();
+    fn cannot_undo(&self, _: &G, _: game::Player) {
+        println!("There is nothing to undo, both players must move first");
+    }
 
-        let action: u8 = buf.trim().parse().unwrap();
+    fn game_over(
+        &self,
+        game: &G,
+        state: &G::State,
+        player: game::Player,
+        winner: Option<game::Player>,
+    ) {
+        println!("{}", game.labelled_board(state));
 
-        action
+        match winner {
+            Some(winner) if winner == player => println!("You won as {player}!"),
+            Some(winner) => println!("You lost, {winner} won."),
+            None => println!("Draw."),
+        }
     }
 
-    fn learn(&mut self, _: &Game) {}
+    fn learn(&mut self, _: &G, _: &mut Rng) {}
 
-    fn save(&self) -> Result<(), ReLearnError> {
+    fn save(&self, _: &G) -> Result<(), ReLearnError> {
         Ok(())
     }
 }
+
+impl<G: Game> HumanPlayer<G> {
+    pub(crate) fn new(hints: Option<MinMaxPlayer<G>>) -> Self {
+        HumanPlayer { hints }
+    }
+
+    /// Asks for a command until a valid one is given, `None` when the input is closed.
+    fn prompt(
+        &self,
+        game: &G,
+        state: &G::State,
+        player: game::Player,
+    ) -> Option<Decision<G::Action>> {
+        let available_moves = game.available_moves(state);
+        let labels = labels(game, &available_moves);
+
+        println!("{}", game.labelled_board(state));
+        println!("You play {player}, your moves: {labels} (type 'help' to see the commands)");
+
+        loop {
+            let input = read_line()?;
+
+            match input.as_str() {
+                "" => {}
+                "undo" => return Some(Decision::Undo),
+                "resign" => return Some(Decision::Resign),
+                "hint" => self.hint(game, state),
+                "help" => println!("{HELP}"),
+                label => match game.parse_label(label) {
+                    Some(action) if available_moves.contains(&action) => {
+                        return Some(Decision::Play(action))
+                    }
+                    _ => println!("'{label}' is not one of your moves: {labels}"),
+                },
+            }
+        }
+    }
+
+    fn hint(&self, game: &G, state: &G::State) {
+        match self
+            .hints
+            .as_ref()
+            .and_then(|hints| hints.solution(game, state))
+        {
+            Some(solution) => println!(
+                "Best moves: {} ({})",
+                labels(game, &solution.best_moves),
+                solution.value
+            ),
+            None => println!(
+                "There are no hints, run 'cargo run -r -- learn min-max --game {}' first",
+                game.name()
+            ),
+        }
+    }
+}
+
+fn labels<G: Game>(game: &G, actions: &[G::Action]) -> String {
+    actions
+        .iter()
+        .map(|&action| game.label(action))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Reads a trimmed line from the standard input, `None` when the input is closed.
+pub(crate) fn read_line() -> Option<String> {
+    print!("> ");
+    io::stdout().flush().ok()?;
+
+    let mut line = String::new();
+
+    match io::stdin().read_line(&mut line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(line.trim().to_string()),
+    }
+}
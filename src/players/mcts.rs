@@ -0,0 +1,252 @@
+use fastrand::Rng;
+
+use crate::{
+    game::{self, Game},
+    ReLearnError,
+};
+
+use super::Player;
+
+/// The `Monte Carlo Tree Search` algorithm, using the `UCT` (Upper Confidence bounds applied to
+/// Trees) selection policy.
+///
+/// Instead of exploring the whole state space graph, it grows a search tree one node per
+/// iteration. Each iteration has four steps:
+/// 1. Selection: starting at the root, go down the tree choosing the child with the highest upper
+///    confidence bound, which balances the children that performed well (exploitation) and the
+///    ones that were seldom visited (exploration).
+/// 2. Expansion: add a child for one of the actions not tried yet.
+/// 3. Simulation: play random moves from the new node until the game ends.
+/// 4. Backpropagation: update the statistics of all the nodes in the path with the result.
+///
+/// After all the iterations it chooses the most visited action. It needs no knowledge about the
+/// game other than its rules, so it plays games with huge state spaces without any learning.
+pub struct MctsPlayer {
+    iterations: u32,
+    /// Higher values give more weight to the exploration of less visited nodes.
+    exploration: f64,
+}
+
+struct Node<G: Game> {
+    action: Option<G::Action>,
+    /// The player that made the `action` that lead to this node.
+    player: game::Player,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    untried_actions: Vec<G::Action>,
+    visits: u32,
+    /// The sum of the results from the point of view of `player` (1 for wins, 0.5 for draws).
+    reward: f64,
+}
+
+impl<G: Game> Player<G> for MctsPlayer {
+    fn play(&self, game: &G, state: &G::State, player: game::Player, rng: &mut Rng) -> G::Action {
+        // The nodes are stored in an arena, the root is always the first one.
+        let mut tree = vec![Node::<G>::new(
+            game,
+            state,
+            None,
+            player.next_player(),
+            None,
+        )];
+
+        for _ in 0..self.iterations {
+            let mut node = 0;
+            let mut state = state.clone();
+
+            // Selection
+            while tree[node].untried_actions.is_empty() && !tree[node].children.is_empty() {
+                node = self.select(&tree, node);
+
+                let (action, player) = (tree[node].action, tree[node].player);
+                // SAFETY: Only the root has no action
+                let action = unsafe { action.unwrap_unchecked() };
+                // SAFETY: The actions are drawn from the `available_moves` method
+                unsafe { game.act(player, action, &mut state).unwrap_unchecked() };
+            }
+
+            // Expansion
+            if !tree[node].untried_actions.is_empty() {
+                let untried_actions = &mut tree[node].untried_actions;
+                let action = untried_actions.swap_remove(rng.usize(..untried_actions.len()));
+                let player = tree[node].player.next_player();
+
+                // SAFETY: The actions are drawn from the `available_moves` method
+                unsafe { game.act(player, action, &mut state).unwrap_unchecked() };
+
+                tree.push(Node::new(game, &state, Some(action), player, Some(node)));
+                let child = tree.len() - 1;
+                tree[node].children.push(child);
+                node = child;
+            }
+
+            // Simulation
+            let mut current_player = tree[node].player.next_player();
+
+            let winner = loop {
+                if let game::Status::Finished(winner) = game.status(&state) {
+                    break winner;
+                }
+
+                let available_moves = game.available_moves(&state);
+                let action = available_moves[rng.usize(..available_moves.len())];
+
+                // SAFETY: The actions are drawn from the `available_moves` method
+                unsafe {
+                    game.act(current_player, action, &mut state)
+                        .unwrap_unchecked()
+                };
+                current_player = current_player.next_player();
+            };
+
+            // Backpropagation
+            let mut maybe_node = Some(node);
+
+            while let Some(node) = maybe_node {
+                let node = &mut tree[node];
+
+                node.visits += 1;
+                node.reward += match winner {
+                    Some(winner) if winner == node.player => 1.0,
+                    Some(_) => 0.0,
+                    None => 0.5,
+                };
+
+                maybe_node = node.parent;
+            }
+        }
+
+        let best_child = tree[0]
+            .children
+            .iter()
+            .max_by_key(|&&child| tree[child].visits)
+            .expect("The agent should only play in non-terminal states");
+
+        // SAFETY: Only the root has no action
+        unsafe { tree[*best_child].action.unwrap_unchecked() }
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("iterations", self.iterations as f64),
+            ("exploration", self.exploration),
+        ]
+    }
+
+    fn learn(&mut self, _: &G, _: &mut Rng) {}
+
+    fn save(&self, _: &G) -> Result<(), ReLearnError> {
+        Ok(())
+    }
+}
+
+impl MctsPlayer {
+    pub(crate) fn new(iterations: u32, exploration: f64) -> Self {
+        MctsPlayer {
+            iterations,
+            exploration,
+        }
+    }
+
+    /// Chooses the child with the highest upper confidence bound.
+    fn select<G: Game>(&self, tree: &[Node<G>], node: usize) -> usize {
+        let log_visits = (tree[node].visits as f64).ln();
+
+        let upper_confidence_bound = |child: usize| {
+            let child = &tree[child];
+            let visits = child.visits as f64;
+
+            child.reward / visits + self.exploration * (log_visits / visits).sqrt()
+        };
+
+        // SAFETY: Only nodes with children are selected
+        unsafe {
+            tree[node]
+                .children
+                .iter()
+                .copied()
+                .max_by(|&a, &b| upper_confidence_bound(a).total_cmp(&upper_confidence_bound(b)))
+                .unwrap_unchecked()
+        }
+    }
+}
+
+impl<G: Game> Node<G> {
+    fn new(
+        game: &G,
+        state: &G::State,
+        action: Option<G::Action>,
+        player: game::Player,
+        parent: Option<usize>,
+    ) -> Self {
+        let untried_actions = match game.status(state) {
+            game::Status::OnGoing => game.available_moves(state),
+            game::Status::Finished(_) => Vec::new(),
+        };
+
+        Node {
+            action,
+            player,
+            parent,
+            children: Vec::new(),
+            untried_actions,
+            visits: 0,
+            reward: 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        game::{IllegalMovePolicy, TicTacToe},
+        players::RandomPlayer,
+    };
+
+    #[test]
+    fn test_tactics() {
+        let game = TicTacToe::default();
+        let player = MctsPlayer::new(1000, std::f64::consts::SQRT_2);
+
+        for seed in 0..5 {
+            let mut rng = Rng::with_seed(seed);
+
+            // X completes its row
+            let state = game.parse_state("XX-|OO-|---").unwrap();
+            assert_eq!(player.play(&game, &state, game::Player::X, &mut rng), 2);
+
+            // X blocks the row of O
+            let state = game.parse_state("X--|OO-|X--").unwrap();
+            assert_eq!(player.play(&game, &state, game::Player::X, &mut rng), 5);
+        }
+    }
+
+    #[test]
+    fn test_play() {
+        let game = TicTacToe::default();
+        let player = MctsPlayer::new(500, std::f64::consts::SQRT_2);
+        let mut rng = Rng::with_seed(0);
+
+        // The random agent never beats the search in Tic-Tac-Toe
+        let victories = (0..50)
+            .filter(|&index| {
+                let (record, seat) = if index % 2 == 0 {
+                    let record =
+                        game.play(&player, &RandomPlayer, IllegalMovePolicy::Forfeit, &mut rng);
+                    (record, game::Player::X)
+                } else {
+                    let record =
+                        game.play(&RandomPlayer, &player, IllegalMovePolicy::Forfeit, &mut rng);
+                    (record, game::Player::O)
+                };
+
+                assert!(record.illegal_moves.is_empty());
+                assert_ne!(record.winner, Some(seat.next_player()));
+                record.winner == Some(seat)
+            })
+            .count();
+
+        assert!(victories > 40, "{victories}");
+    }
+}
@@ -1,5 +1,6 @@
-use std::fs::File;
+use std::{cmp::Ordering, collections::HashMap, fmt::Display, fs::File};
 
+use fastrand::Rng;
 use rmp_serde::Serializer;
 use serde::{Deserialize, Serialize};
 
@@ -12,47 +13,78 @@ use serde::{Deserialize, Serialize};
 /// This algorithm is unsuitable for large search space games as it needs to explore all
 /// the possible states before taking a decision, which become unfeasible very fast.
 ///
-/// NOTE: This algorithm was customized to stop evaluating upon reaching the first terminal state
-/// with victory as it's not possible to have any higher score.
+/// NOTE: This implementation solves the game, it stores the game-theoretic value and all the
+/// optimal moves of every reachable state. The states are solved from the point of view of the
+/// player to move, so the opponent's value is just the negation of ours (also known as `Negamax`).
+/// The agent then plays a random optimal move, preferring faster wins and slower losses.
 use crate::{
-    game::{self, Game, State},
+    game::{self, Game, Symmetry},
     ReLearnError,
 };
 
-use super::Player;
-use serde_big_array::BigArray;
+use super::{search_move, Player};
 
-pub const FILE: &str = "minmax.bin";
-// We would only obtain this number if all fields were filled by Player::O
-pub const STATE_SIZE: usize = 19682;
+/// The file where the learned agent is stored, one for each game.
+pub fn file<G: Game>(game: &G) -> String {
+    format!("minmax-{}.bin", game.name())
+}
 
 #[derive(Serialize, Deserialize)]
-pub struct MinMaxPlayer {
-    #[serde(with = "BigArray")]
-    knowledge: [u8; STATE_SIZE],
+#[serde(bound = "")]
+pub struct MinMaxPlayer<G: Game> {
+    /// The solution of each non-terminal encoded state, see `Game::encode`.
+    knowledge: HashMap<u64, Solution<G>>,
+    /// Stores a single solution for all the symmetric states, keyed by `Game::canonical_encode`,
+    /// so the best moves are stored as played in the canonical form of the state.
+    #[serde(default)]
+    symmetric: bool,
 }
 
-impl Player for MinMaxPlayer {
-    fn play(&self, state: &State, _: game::Player) -> u8 {
-        // SAFETY: We always train the player before playing
-        unsafe {
-            *self
-                .knowledge
-                .get(state.encoded_state as usize)
-                .unwrap_unchecked()
-        }
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Solution<G: Game> {
+    pub value: Value,
+    /// All the moves that lead to `value`.
+    pub best_moves: Vec<G::Action>,
+}
+
+/// The game-theoretic value of a state for the player to move, when both players play perfectly.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Value {
+    pub outcome: Outcome,
+    /// How many moves until the end of the game.
+    pub distance: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Outcome {
+    Win,
+    Draw,
+    Loss,
+}
+
+impl<G: Game> Player<G> for MinMaxPlayer<G> {
+    /// Plays a random legal move in the states it didn't reach while learning (e.g., when it's not
+    /// trained), instead of stopping the whole match.
+    fn play(&self, game: &G, state: &G::State, _: game::Player, rng: &mut Rng) -> G::Action {
+        let best_moves = match self.solution(game, state) {
+            Some(solution) => solution.best_moves,
+            None => game.available_moves(state),
+        };
+
+        best_moves[rng.usize(..best_moves.len())]
     }
 
-    fn learn(&mut self, game: &Game) {
-        let state = State::new();
-        let player = game::Player::X;
+    fn learn(&mut self, game: &G, _: &mut Rng) {
+        let mut state = game.initial_state();
+        let player = game.current_player(&state);
 
-        self.maximize(game, state, player);
+        self.solve(game, &mut state, player);
     }
 
-    fn save(&self) -> Result<(), ReLearnError> {
-        let mut file =
-            File::create(FILE).map_err(|err| ReLearnError::SaveAgentError(err.to_string()))?;
+    fn save(&self, game: &G) -> Result<(), ReLearnError> {
+        let mut file = File::create(file(game))
+            .map_err(|err| ReLearnError::SaveAgentError(err.to_string()))?;
 
         // We use the `rmp_serde` instead of `serde_json` for two reasons:
         // 1. It's a compact format, reducing the learned agent size in disk
@@ -65,84 +97,266 @@ impl Player for MinMaxPlayer {
     }
 }
 
-impl MinMaxPlayer {
-    pub(crate) fn new() -> Self {
+impl<G: Game> MinMaxPlayer<G> {
+    pub(crate) fn new(symmetric: bool) -> Self {
         MinMaxPlayer {
-            knowledge: [u8::MAX; STATE_SIZE],
+            knowledge: HashMap::new(),
+            symmetric,
         }
     }
 
-    fn maximize(&mut self, game: &Game, state: State, player: game::Player) -> (i64, Option<u8>) {
-        if let game::Status::Finished(maybe_winner) = Game::status(&state) {
-            return (Self::utility(maybe_winner, player), None);
-        }
-
-        // We use a value that any move will surpass, just to initialize the variable
-        let mut highest_value = -10;
-        let mut best_move: Option<_> = None;
+    /// The solution of the state, `None` if the state is terminal or it was not reached while
+    /// learning.
+    pub(crate) fn solution(&self, game: &G, state: &G::State) -> Option<Solution<G>> {
+        let (encoded_state, symmetry) = self.encode(game, state);
 
-        for &action in Game::available_moves(&state) {
-            let mut next_state = state.clone();
-
-            // SAFETY: we draw the actions from the `available_moves` method
-            unsafe { Game::act(player, action, &mut next_state).unwrap_unchecked() };
-
-            let (action_value, _) = self.minimize(game, next_state, player);
+        self.knowledge.get(&encoded_state).map(|solution| Solution {
+            value: solution.value,
+            best_moves: solution
+                .best_moves
+                .iter()
+                .map(|&action| game.transform_action(action, symmetry.inverse()))
+                .collect(),
+        })
+    }
 
-            if action_value > highest_value {
-                highest_value = action_value;
-                best_move = Some(action);
-            }
+    /// The key of the state in the `knowledge` and the symmetry that turns the moves of the state
+    /// into the stored ones.
+    fn encode(&self, game: &G, state: &G::State) -> (u64, Symmetry) {
+        if self.symmetric {
+            game.canonical_encode(state)
+        } else {
+            (game.encode(state), Symmetry::IDENTITY)
         }
+    }
 
-        // SAFETY: Only terminal states have `None` as the action, but in terminal states the game
-        // is already finished.
-        let action = unsafe { best_move.unwrap_unchecked() };
-        self.knowledge[state.encoded_state as usize] = action;
+    /// The value of each available move for the `player` to move, the next states are solved as
+    /// needed.
+    pub(crate) fn action_values(
+        &mut self,
+        game: &G,
+        state: &G::State,
+        player: game::Player,
+    ) -> Vec<(G::Action, Value)> {
+        self.solve_actions(game, &mut state.clone(), player)
+    }
 
-        (highest_value, best_move)
+    /// The same as `action_values`, making and unmaking the moves in the `state`, see
+    /// `search_move`.
+    fn solve_actions(
+        &mut self,
+        game: &G,
+        state: &mut G::State,
+        player: game::Player,
+    ) -> Vec<(G::Action, Value)> {
+        game.available_moves(state)
+            .into_iter()
+            .map(|action| {
+                let action_value = search_move(game, state, player, action, |next_state| {
+                    self.solve(game, next_state, player.next_player())
+                })
+                .for_opponent();
+
+                (action, action_value)
+            })
+            .collect()
     }
 
-    fn minimize(&mut self, game: &Game, state: State, player: game::Player) -> (i64, Option<u8>) {
-        if let game::Status::Finished(maybe_winner) = Game::status(&state) {
-            return (Self::utility(maybe_winner, player), None);
+    /// Solves the state for the `player` to move, storing the solution of every state reachable
+    /// from it. The `state` is left as it was.
+    fn solve(&mut self, game: &G, state: &mut G::State, player: game::Player) -> Value {
+        if let game::Status::Finished(maybe_winner) = game.status(state) {
+            return Value::terminal(maybe_winner, player);
         }
 
-        let mut lowest_value = 10;
-        let mut worst_move: Option<_> = None;
+        let (encoded_state, symmetry) = self.encode(game, state);
+
+        // The same state can be reached by different sequences of moves
+        if let Some(solution) = self.knowledge.get(&encoded_state) {
+            return solution.value;
+        }
 
-        for &action in Game::available_moves(&state) {
-            let mut next_state = state.clone();
-            // SAFETY: we draw the actions from the `available_moves` method
-            unsafe {
-                Game::act(player.next_player(), action, &mut next_state).unwrap_unchecked();
-            };
-            let (action_value, _) = self.maximize(game, next_state, player);
+        let mut best_value: Option<Value> = None;
+        let mut best_moves = Vec::new();
 
-            if action_value < lowest_value {
-                lowest_value = action_value;
-                worst_move = Some(action);
+        for (action, action_value) in self.solve_actions(game, state, player) {
+            match best_value.map(|value| action_value.score().cmp(&value.score())) {
+                None | Some(Ordering::Greater) => {
+                    best_value = Some(action_value);
+                    best_moves = vec![action];
+                }
+                Some(Ordering::Equal) => best_moves.push(action),
+                Some(Ordering::Less) => {}
             }
         }
 
-        // SAFETY: Only terminal states have `None` as the action, but in terminal states the game
+        // SAFETY: Only terminal states have no available moves, but in terminal states the game
         // is already finished.
-        let action = unsafe { worst_move.unwrap_unchecked() };
-        self.knowledge[state.encoded_state as usize] = action;
+        let value = unsafe { best_value.unwrap_unchecked() };
+        let best_moves = best_moves
+            .into_iter()
+            .map(|action| game.transform_action(action, symmetry))
+            .collect();
+        self.knowledge
+            .insert(encoded_state, Solution { value, best_moves });
 
-        (lowest_value, worst_move)
+        value
     }
+}
 
-    fn utility(maybe_winner: Option<game::Player>, player: game::Player) -> i64 {
-        match maybe_winner {
+impl Value {
+    fn terminal(maybe_winner: Option<game::Player>, player: game::Player) -> Self {
+        let outcome = match maybe_winner {
             Some(winner) => {
                 if winner == player {
-                    1
+                    Outcome::Win
                 } else {
-                    -1
+                    Outcome::Loss
                 }
             }
-            None => 0,
+            None => Outcome::Draw,
+        };
+
+        Value {
+            outcome,
+            distance: 0,
+        }
+    }
+
+    /// The value of the state for the opponent, one move before reaching it.
+    fn for_opponent(self) -> Self {
+        let outcome = match self.outcome {
+            Outcome::Win => Outcome::Loss,
+            Outcome::Draw => Outcome::Draw,
+            Outcome::Loss => Outcome::Win,
+        };
+
+        Value {
+            outcome,
+            distance: self.distance + 1,
+        }
+    }
+
+    /// Orders the values, the faster wins are better than the slower ones and the slower losses are
+    /// better than the faster ones. All the draws are worth the same.
+    pub(crate) fn score(&self) -> i64 {
+        match self.outcome {
+            Outcome::Win => i64::from(u32::MAX) - i64::from(self.distance),
+            Outcome::Draw => 0,
+            Outcome::Loss => -i64::from(u32::MAX) + i64::from(self.distance),
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let outcome = match self.outcome {
+            Outcome::Win => "Win",
+            Outcome::Draw => "Draw",
+            Outcome::Loss => "Loss",
+        };
+        let moves = if self.distance == 1 { "move" } else { "moves" };
+
+        write!(f, "{outcome} in {} {moves}", self.distance)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::{TicTacToe, WildTicTacToe};
+
+    #[test]
+    fn test_solve() {
+        for symmetric in [false, true] {
+            solve(symmetric);
+        }
+    }
+
+    fn solve(symmetric: bool) {
+        let mut player = MinMaxPlayer::new(symmetric);
+        player.learn(&TicTacToe::default(), &mut Rng::new());
+
+        // All the non-terminal states, or one for each group of symmetric states
+        assert_eq!(player.knowledge.len(), if symmetric { 627 } else { 4520 });
+
+        let mut state = TicTacToe::default().initial_state();
+        let solution = player.solution(&TicTacToe::default(), &state).unwrap();
+        assert_eq!(solution.value.outcome, Outcome::Draw);
+        assert_eq!(solution.value.distance, 9);
+        assert_eq!(solution.best_moves.len(), 9);
+
+        for (player, action) in [
+            (game::Player::X, 0),
+            (game::Player::O, 3),
+            (game::Player::X, 1),
+            (game::Player::O, 4),
+        ] {
+            TicTacToe::default()
+                .act(player, action, &mut state)
+                .unwrap();
+        }
+
+        // X wins right away on the top row, instead of blocking the middle row
+        let solution = player.solution(&TicTacToe::default(), &state).unwrap();
+        assert_eq!(solution.value.outcome, Outcome::Win);
+        assert_eq!(solution.value.distance, 1);
+        assert_eq!(solution.best_moves, vec![2]);
+
+        TicTacToe::default()
+            .act(game::Player::X, 8, &mut state)
+            .unwrap();
+
+        // X missed the victory, now O wins right away on the middle row
+        let solution = player.solution(&TicTacToe::default(), &state).unwrap();
+        assert_eq!(solution.value.outcome, Outcome::Win);
+        assert_eq!(solution.best_moves, vec![5]);
+    }
+
+    #[test]
+    fn test_untrained() {
+        let game = TicTacToe::default();
+        let player = MinMaxPlayer::new(false);
+        let state = game.parse_state("XX-|OO-|---").unwrap();
+
+        let action = player.play(&game, &state, game::Player::X, &mut Rng::with_seed(0));
+        assert!(game.available_moves(&state).contains(&action));
+    }
+
+    #[test]
+    fn test_variants() {
+        // In misère Tic-Tac-Toe only the center avoids the loss
+        let game = TicTacToe::new(true);
+        let mut player = MinMaxPlayer::new(false);
+        player.learn(&game, &mut Rng::new());
+
+        let solution = player.solution(&game, &game.initial_state()).unwrap();
+        assert_eq!(solution.value.outcome, Outcome::Draw);
+        assert_eq!(solution.best_moves, vec![4]);
+
+        // X doesn't complete the top row, which would lose right away
+        let state = game.parse_state("XX-|OO-|---").unwrap();
+        let solution = player.solution(&game, &state).unwrap();
+        assert_eq!(solution.value.outcome, Outcome::Draw);
+        assert!(!solution.best_moves.contains(&2));
+
+        // In wild Tic-Tac-Toe the first player starts with either piece in the center, which wins
+        // the normal game and draws the misère one
+        for (misere, outcome) in [(false, Outcome::Win), (true, Outcome::Draw)] {
+            let game = WildTicTacToe::new(misere);
+            let mut player = MinMaxPlayer::new(true);
+            player.learn(&game, &mut Rng::new());
+
+            let solution = player.solution(&game, &game.initial_state()).unwrap();
+            assert_eq!(solution.value.outcome, outcome);
+            assert_eq!(
+                solution
+                    .best_moves
+                    .iter()
+                    .map(|&action| game.label(action))
+                    .collect::<Vec<_>>(),
+                vec!["Xb2", "Ob2"]
+            );
         }
     }
 }
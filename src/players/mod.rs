@@ -1,18 +1,105 @@
+mod alphabeta;
+pub mod cfr;
+mod expectiminimax;
+mod grundy;
 mod human;
+mod mcts;
 pub mod minmax;
+pub mod qlearning;
 mod random;
 
-pub(crate) use human::HumanPlayer;
+pub(crate) use alphabeta::AlphaBetaPlayer;
+pub(crate) use cfr::CfrPlayer;
+pub(crate) use expectiminimax::ExpectiminimaxPlayer;
+pub(crate) use grundy::SpragueGrundyPlayer;
+pub(crate) use human::{read_line, HumanPlayer};
+pub(crate) use mcts::MctsPlayer;
 pub(crate) use minmax::MinMaxPlayer;
+pub(crate) use qlearning::{QLearningParameters, QLearningPlayer};
 pub(crate) use random::RandomPlayer;
 
+use fastrand::Rng;
+
 use crate::{
-    game::{self, Game, State},
+    game::{self, Game},
     ReLearnError,
 };
 
-pub trait Player: Sync + Send {
-    fn play(&self, state: &State, player: game::Player) -> u8;
-    fn learn(&mut self, game: &Game);
-    fn save(&self) -> Result<(), ReLearnError>;
+/// The agents draw all their random numbers from the given `rng`, so the same seed always leads
+/// to the same moves.
+///
+/// The players decide with their information set (see `Game::information_set`), which is the whole
+/// state in the games without hidden information.
+pub trait Player<G: Game>: Sync + Send {
+    fn play(&self, game: &G, state: &G::State, player: game::Player, rng: &mut Rng) -> G::Action;
+
+    /// What the player does in its turn, the agents always play.
+    fn decide(
+        &self,
+        game: &G,
+        state: &G::State,
+        player: game::Player,
+        rng: &mut Rng,
+    ) -> Decision<G::Action> {
+        Decision::Play(self.play(game, state, player, rng))
+    }
+
+    /// Called when the player asked to undo before both players moved, so there was nothing to take
+    /// back. The player decides again.
+    fn cannot_undo(&self, _game: &G, _player: game::Player) {}
+
+    /// Called with the final state when the game ends.
+    fn game_over(
+        &self,
+        _game: &G,
+        _state: &G::State,
+        _player: game::Player,
+        _winner: Option<game::Player>,
+    ) {
+    }
+
+    /// The parameters that change how the player plays, named as in the reports. The learned
+    /// agents have the ones they learned with.
+    fn parameters(&self) -> Vec<(&'static str, f64)> {
+        Vec::new()
+    }
+
+    fn learn(&mut self, game: &G, rng: &mut Rng);
+    fn save(&self, game: &G) -> Result<(), ReLearnError>;
+}
+
+/// What a player does in its turn, see `Player::decide`.
+pub enum Decision<A> {
+    Play(A),
+    /// Takes back the last move of each player.
+    Undo,
+    Resign,
+}
+
+/// Plays the `action` of the `player`, searches the next state and takes the action back, so the
+/// `state` is left as it was. The move is made and unmade in place in the games that can undo their
+/// moves (see `Game::undo`), the others play it in a copy of the state.
+fn search_move<G: Game, T>(
+    game: &G,
+    state: &mut G::State,
+    player: game::Player,
+    action: G::Action,
+    search: impl FnOnce(&mut G::State) -> T,
+) -> T {
+    if game.can_undo() {
+        // SAFETY: the searches draw the actions from the `available_moves` method
+        unsafe { game.act(player, action, state).unwrap_unchecked() };
+
+        let value = search(state);
+        game.undo(player, action, state);
+
+        value
+    } else {
+        let mut next_state = state.clone();
+
+        // SAFETY: the searches draw the actions from the `available_moves` method
+        unsafe { game.act(player, action, &mut next_state).unwrap_unchecked() };
+
+        search(&mut next_state)
+    }
 }
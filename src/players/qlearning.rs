@@ -0,0 +1,318 @@
+use std::{collections::HashMap, fs::File};
+
+use fastrand::Rng;
+use rmp_serde::Serializer;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game::{self, Game, Symmetry},
+    ReLearnError,
+};
+
+use super::Player;
+
+/// The file where the learned agent is stored, one for each game.
+pub fn file<G: Game>(game: &G) -> String {
+    format!("qlearning-{}.bin", game.name())
+}
+
+/// The `Q-Learning` algorithm is a model-free reinforcement learning algorithm.
+///
+/// It learns the value of taking each action in each state (the `Q` value) by trial and error,
+/// nudging the current estimate towards the reward received plus the (discounted) value of the
+/// next state. Here the agent learns by playing against itself, so the values are always from the
+/// point of view of the player to move and the value of the next state is the negation of the
+/// opponent's best action value.
+///
+/// While learning it follows an epsilon-greedy policy: with probability `epsilon` it explores a
+/// random action, otherwise it exploits the best known one. `epsilon` decays linearly along the
+/// episodes, so the agent explores a lot at the beginning and refines its policy at the end.
+///
+/// The states are stored in a table, so it's only suitable for games with small state spaces.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct QLearningPlayer<G: Game> {
+    /// The value of each action for each encoded state, see `Game::encode`.
+    q_table: HashMap<u64, HashMap<G::Action, f64>>,
+    parameters: QLearningParameters,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct QLearningParameters {
+    /// How many games the agent plays against itself while learning.
+    pub episodes: u32,
+    /// How much each update moves the value towards the new estimate, between 0 and 1.
+    pub learning_rate: f64,
+    /// How much the future rewards are worth compared to the immediate ones, between 0 and 1.
+    pub discount: f64,
+    /// The exploration probability in the first episode.
+    pub epsilon_start: f64,
+    /// The exploration probability in the last episode.
+    pub epsilon_end: f64,
+    /// Shares the values between the symmetric states, keyed by `Game::canonical_encode`, so the
+    /// agent generalizes what it learns in one state to all the states symmetric to it.
+    #[serde(default)]
+    pub symmetric: bool,
+}
+
+impl<G: Game> Player<G> for QLearningPlayer<G> {
+    fn play(&self, game: &G, state: &G::State, _: game::Player, rng: &mut Rng) -> G::Action {
+        self.best_action(game, state, rng)
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, f64)> {
+        let parameters = &self.parameters;
+
+        vec![
+            ("episodes", parameters.episodes as f64),
+            ("learning_rate", parameters.learning_rate),
+            ("discount", parameters.discount),
+            ("epsilon_start", parameters.epsilon_start),
+            ("epsilon_end", parameters.epsilon_end),
+        ]
+    }
+
+    fn learn(&mut self, game: &G, rng: &mut Rng) {
+        let QLearningParameters {
+            episodes,
+            learning_rate,
+            discount,
+            ..
+        } = self.parameters;
+
+        for episode in 0..episodes {
+            let epsilon = self.parameters.epsilon(episode);
+
+            let mut state = game.initial_state();
+            game.sample_chance_events(&mut state, rng);
+            let mut player = game.current_player(&state);
+
+            // Some games are over before the first move, there is nothing to learn
+            if game.status(&state) != game::Status::OnGoing {
+                break;
+            }
+
+            loop {
+                let action = if rng.f64() < epsilon {
+                    let available_moves = game.available_moves(&state);
+                    available_moves[rng.usize(..available_moves.len())]
+                } else {
+                    self.best_action(game, &state, rng)
+                };
+
+                let (encoded_state, symmetry) = self.encode(game, &state);
+
+                // SAFETY: we draw the actions from the `available_moves` method
+                unsafe { game.act(player, action, &mut state).unwrap_unchecked() };
+                // The chance events are part of the environment, the next state is the one where
+                // the opponent decides
+                game.sample_chance_events(&mut state, rng);
+
+                let status = game.status(&state);
+                let target = match status {
+                    game::Status::Finished(maybe_winner) => Self::reward(maybe_winner, player),
+                    // The next state belongs to the opponent, its best value is our worst.
+                    game::Status::OnGoing => -discount * self.highest_value(game, &state),
+                };
+
+                let value = self
+                    .q_table
+                    .entry(encoded_state)
+                    .or_default()
+                    .entry(game.transform_action(action, symmetry))
+                    .or_default();
+                *value += learning_rate * (target - *value);
+
+                if status != game::Status::OnGoing {
+                    break;
+                }
+
+                player = player.next_player();
+            }
+        }
+    }
+
+    fn save(&self, game: &G) -> Result<(), ReLearnError> {
+        let mut file = File::create(file(game))
+            .map_err(|err| ReLearnError::SaveAgentError(err.to_string()))?;
+
+        self.serialize(&mut Serializer::new(&mut file))
+            .map_err(|err| ReLearnError::SaveAgentError(err.to_string()))
+    }
+}
+
+impl<G: Game> QLearningPlayer<G> {
+    pub(crate) fn new(parameters: QLearningParameters) -> Self {
+        QLearningPlayer {
+            q_table: HashMap::new(),
+            parameters,
+        }
+    }
+
+    /// The action with the highest value, the ties are broken randomly. Unknown actions are worth 0.
+    fn best_action(&self, game: &G, state: &G::State, rng: &mut Rng) -> G::Action {
+        let (encoded_state, symmetry) = self.encode(game, state);
+        let values = self.q_table.get(&encoded_state);
+        let value = |action: G::Action| {
+            values
+                .and_then(|values| values.get(&game.transform_action(action, symmetry)))
+                .copied()
+                .unwrap_or_default()
+        };
+
+        let available_moves = game.available_moves(state);
+        let highest_value = available_moves
+            .iter()
+            .map(|&action| value(action))
+            .fold(f64::NEG_INFINITY, f64::max);
+
+        let best_moves: Vec<_> = available_moves
+            .into_iter()
+            .filter(|&action| value(action) == highest_value)
+            .collect();
+
+        best_moves[rng.usize(..best_moves.len())]
+    }
+
+    fn highest_value(&self, game: &G, state: &G::State) -> f64 {
+        let (encoded_state, symmetry) = self.encode(game, state);
+        let Some(values) = self.q_table.get(&encoded_state) else {
+            return 0.0;
+        };
+
+        game.available_moves(state)
+            .into_iter()
+            .map(|action| {
+                values
+                    .get(&game.transform_action(action, symmetry))
+                    .copied()
+                    .unwrap_or_default()
+            })
+            .fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    /// The key of the state in the `q_table` and the symmetry that turns the actions of the state
+    /// into the stored ones.
+    fn encode(&self, game: &G, state: &G::State) -> (u64, Symmetry) {
+        if self.parameters.symmetric {
+            game.canonical_encode(state)
+        } else {
+            (game.encode(state), Symmetry::IDENTITY)
+        }
+    }
+
+    fn reward(maybe_winner: Option<game::Player>, player: game::Player) -> f64 {
+        match maybe_winner {
+            Some(winner) => {
+                if winner == player {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            None => 0.0,
+        }
+    }
+}
+
+impl QLearningParameters {
+    /// The exploration probability in the `episode`, from `epsilon_start` in the first one to
+    /// `epsilon_end` in the last one.
+    fn epsilon(&self, episode: u32) -> f64 {
+        let progress = episode as f64 / self.episodes.saturating_sub(1).max(1) as f64;
+
+        self.epsilon_start + (self.epsilon_end - self.epsilon_start) * progress
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        game::{IllegalMovePolicy, Nim, TicTacToe},
+        players::RandomPlayer,
+    };
+
+    fn parameters(episodes: u32, epsilon: f64) -> QLearningParameters {
+        QLearningParameters {
+            episodes,
+            learning_rate: 0.5,
+            discount: 0.9,
+            epsilon_start: epsilon,
+            epsilon_end: epsilon,
+            symmetric: true,
+        }
+    }
+
+    #[test]
+    fn test_update() {
+        // A heap of 2 where only 1 piece is taken at once, so O always takes the last one
+        let game = Nim::new(vec![2], Some(vec![1]), false);
+        let mut player = QLearningPlayer::new(parameters(1, 0.0));
+        let take = "1:1".parse().unwrap();
+        let value = |player: &QLearningPlayer<Nim>, board: &str| {
+            let state = game.parse_state(board).unwrap();
+            player.q_table[&game.encode(&state)][&take]
+        };
+
+        // O's victory is rewarded, X's move leads to a state worth 0 for O
+        player.learn(&game, &mut Rng::with_seed(0));
+        assert_eq!(value(&player, "1"), 0.5);
+        assert_eq!(value(&player, "2"), 0.0);
+
+        // X's move is worth the discounted value of O's best move, negated
+        player.learn(&game, &mut Rng::with_seed(0));
+        assert_eq!(value(&player, "1"), 0.75);
+        assert_eq!(value(&player, "2"), 0.5 * -0.9 * 0.5);
+    }
+
+    #[test]
+    fn test_epsilon() {
+        let decaying = QLearningParameters {
+            epsilon_end: 0.1,
+            ..parameters(11, 1.0)
+        };
+
+        assert_eq!(decaying.epsilon(0), 1.0);
+        assert!((decaying.epsilon(5) - 0.55).abs() < 1e-9);
+        assert!((decaying.epsilon(10) - 0.1).abs() < 1e-9);
+
+        // A single episode uses the initial exploration
+        assert_eq!(parameters(1, 0.3).epsilon(0), 0.3);
+    }
+
+    #[test]
+    fn test_serialization() {
+        let game = TicTacToe::default();
+        let mut player = QLearningPlayer::new(parameters(1000, 1.0));
+        player.learn(&game, &mut Rng::with_seed(0));
+
+        // Written and read as the learned agent files
+        let mut bytes = Vec::new();
+        player.serialize(&mut Serializer::new(&mut bytes)).unwrap();
+        let loaded_player = QLearningPlayer::<TicTacToe>::deserialize(
+            &mut rmp_serde::Deserializer::new(&bytes[..]),
+        )
+        .unwrap();
+
+        assert_eq!(loaded_player.q_table, player.q_table);
+        assert!(loaded_player.parameters.symmetric);
+    }
+
+    #[test]
+    fn test_play() {
+        let game = TicTacToe::default();
+        let mut player = QLearningPlayer::new(QLearningParameters {
+            epsilon_end: 0.05,
+            ..parameters(20_000, 1.0)
+        });
+        player.learn(&game, &mut Rng::with_seed(0));
+
+        // As X it never loses against the random agent
+        let mut rng = Rng::with_seed(0);
+        for _ in 0..200 {
+            let record = game.play(&player, &RandomPlayer, IllegalMovePolicy::Forfeit, &mut rng);
+            assert_ne!(record.winner, Some(game::Player::O));
+        }
+    }
+}
@@ -1,22 +1,23 @@
+use fastrand::Rng;
 use serde::{Deserialize, Serialize};
 
-use crate::game::{self, Game, State};
+use crate::game::{self, Game};
 
 use super::Player;
 
 #[derive(Serialize, Deserialize)]
 pub struct RandomPlayer;
 
-impl Player for RandomPlayer {
-    fn play(&self, state: &State, _: game::Player) -> u8 {
-        let available_moves = Game::available_moves(state);
-        let i = fastrand::usize(..available_moves.len());
+impl<G: Game> Player<G> for RandomPlayer {
+    fn play(&self, game: &G, state: &G::State, _: game::Player, rng: &mut Rng) -> G::Action {
+        let available_moves = game.available_moves(state);
+        let i = rng.usize(..available_moves.len());
         available_moves[i]
     }
 
-    fn learn(&mut self, _: &Game) {}
+    fn learn(&mut self, _: &G, _: &mut Rng) {}
 
-    fn save(&self) -> Result<(), super::ReLearnError> {
+    fn save(&self, _: &G) -> Result<(), super::ReLearnError> {
         Ok(())
     }
 }